@@ -0,0 +1,267 @@
+use crate::core::*;
+use crate::types::*;
+use crate::utils::*;
+
+pub const UART_ADDRESS: Address = 0x8000_0000;
+pub const UART_SIZE: Address = 8;
+pub const CYCLE_COUNTER_ADDRESS: Address = 0x8000_1000;
+pub const CYCLE_COUNTER_SIZE: Address = 16;
+
+/// A memory-mapped device. `offset` is relative to the start of the range the device claims.
+pub trait Device {
+    fn get_name(&self) -> String;
+    /// Load of `size` bytes (1, 2 or 4) with the value in the low bits. Byte and halfword
+    /// loads keep their offset within the word, so a load next to a register with side
+    /// effects does not trigger them.
+    fn load(&mut self, core: &mut Core, offset: Address, size: Address) -> Word;
+    /// Load by `flw`, for devices that return floating-point values in a different form.
+    fn load_word_fp(&mut self, core: &mut Core, offset: Address) -> Word {
+        self.load(core, offset, 4)
+    }
+    /// Store of `size` bytes (1, 2 or 4) with the value in the low bits. Byte and halfword
+    /// stores keep their offset within the word, so they can be told apart from word stores.
+    fn store(&mut self, core: &mut Core, offset: Address, value: Word, size: Address);
+}
+
+struct MappedDevice {
+    start: Address,
+    size: Address,
+    device: Box<dyn Device>,
+}
+
+/// Address decoder between the core and memory. Accesses to addresses claimed by
+/// a device go to the device, and the rest go to the cache and memory.
+pub struct Bus {
+    devices: Vec<MappedDevice>,
+}
+
+impl Bus {
+    pub fn new() -> Self {
+        Bus { devices: vec![] }
+    }
+
+    pub fn attach(
+        &mut self,
+        start: Address,
+        size: Address,
+        device: Box<dyn Device>,
+    ) -> Result<(), String> {
+        let end = start as u64 + size as u64;
+        if size == 0 || end > 1 << 32 {
+            return Err(format!(
+                "invalid range {:#x}+{:#x} for {}",
+                start,
+                size,
+                device.get_name()
+            ));
+        }
+        for mapped in &self.devices {
+            if (start as u64) < mapped.start as u64 + mapped.size as u64
+                && (mapped.start as u64) < end
+            {
+                return Err(format!(
+                    "{} at {:#x} overlaps {} at {:#x}",
+                    device.get_name(),
+                    start,
+                    mapped.device.get_name(),
+                    mapped.start
+                ));
+            }
+        }
+        self.devices.push(MappedDevice {
+            start,
+            size,
+            device,
+        });
+        Ok(())
+    }
+
+    fn find_device(&mut self, addr: Address) -> Option<(&mut Box<dyn Device>, Address)> {
+        self.devices
+            .iter_mut()
+            .find(|mapped| addr >= mapped.start && addr - mapped.start < mapped.size)
+            .map(|mapped| (&mut mapped.device, addr - mapped.start))
+    }
+
+    /// Returns `None` if no device claims `addr`.
+    pub fn load(&mut self, core: &mut Core, addr: Address, size: Address) -> Option<Word> {
+        self.find_device(addr)
+            .map(|(device, offset)| device.load(core, offset, size))
+    }
+
+    /// Returns `None` if no device claims `addr`.
+    pub fn load_word_fp(&mut self, core: &mut Core, addr: Address) -> Option<Word> {
+        self.find_device(addr)
+            .map(|(device, offset)| device.load_word_fp(core, offset))
+    }
+
+    /// Returns false if no device claims `addr`.
+    pub fn store(&mut self, core: &mut Core, addr: Address, value: Word, size: Address) -> bool {
+        match self.find_device(addr) {
+            Some((device, offset)) => {
+                device.store(core, offset, value, size);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// UART with the semantics of the old `IO_ADDRESS` design.
+/// Offset 0: a load reads the next token of the sld file (as a float for `flw`; loads of
+/// bytes 1 to 3 read 0 without consuming it),
+/// and a store writes the low byte to the output (stores to bytes 1 to 3 are ignored).
+/// Offset 4: status, bit 0 is set while input remains and bit 1 (transmitter ready) is always set.
+pub struct Uart;
+
+impl Device for Uart {
+    fn get_name(&self) -> String {
+        "uart".to_string()
+    }
+
+    fn load(&mut self, core: &mut Core, offset: Address, _: Address) -> Word {
+        match offset {
+            0 => core.read_int(),
+            4 => core.has_input() as Word | 0b10,
+            _ => 0,
+        }
+    }
+
+    fn load_word_fp(&mut self, core: &mut Core, offset: Address) -> Word {
+        match offset {
+            0 => core.read_float(),
+            _ => self.load(core, offset, 4),
+        }
+    }
+
+    fn store(&mut self, core: &mut Core, offset: Address, value: Word, _: Address) {
+        if offset == 0 {
+            core.print_char(value);
+        }
+    }
+}
+
+/// Read-only counters. Offsets 0/4: low/high words of the cycle count,
+/// offsets 8/12: low/high words of the instruction count.
+pub struct CycleCounter;
+
+impl Device for CycleCounter {
+    fn get_name(&self) -> String {
+        "cycle counter".to_string()
+    }
+
+    fn load(&mut self, core: &mut Core, offset: Address, _: Address) -> Word {
+        let value = match offset & !3 {
+            0 => core.get_cycle_count(),
+            4 => core.get_cycle_count() >> 32,
+            8 => core.get_instruction_count(),
+            12 => core.get_instruction_count() >> 32,
+            _ => 0,
+        };
+        u32_to_i32((value as u32) >> ((offset & 3) * 8))
+    }
+
+    fn store(&mut self, _: &mut Core, _: Address, _: Word, _: Address) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::tests::run_program;
+
+    fn core_with_devices() -> Core {
+        let mut core = Core::new();
+        let mut bus = Bus::new();
+        bus.attach(UART_ADDRESS, UART_SIZE, Box::new(Uart)).unwrap();
+        bus.attach(
+            CYCLE_COUNTER_ADDRESS,
+            CYCLE_COUNTER_SIZE,
+            Box::new(CycleCounter),
+        )
+        .unwrap();
+        core.set_bus(bus);
+        core
+    }
+
+    #[test]
+    fn test_attach() {
+        let mut bus = Bus::new();
+        bus.attach(UART_ADDRESS, UART_SIZE, Box::new(Uart)).unwrap();
+        assert!(bus
+            .attach(UART_ADDRESS + 4, 8, Box::new(CycleCounter))
+            .is_err());
+        assert!(bus
+            .attach(UART_ADDRESS - 4, 8, Box::new(CycleCounter))
+            .is_err());
+        assert!(bus.attach(0x1000, 0, Box::new(CycleCounter)).is_err());
+        assert!(bus.attach(0xffff_fff0, 32, Box::new(CycleCounter)).is_err());
+        bus.attach(UART_ADDRESS - 4, 4, Box::new(CycleCounter))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_uart() {
+        let mut core = core_with_devices();
+        core.store_word(UART_ADDRESS, 0x1234_5641);
+        core.store_word(UART_ADDRESS + 4, 0x42);
+        assert_eq!(core.get_output(), b"A");
+        // no input and the transmitter is ready
+        assert_eq!(core.load_word(UART_ADDRESS + 4), 0b10);
+        assert_eq!(core.load_ubyte(UART_ADDRESS + 4), 0b10);
+        // addresses next to the devices go to memory
+        core.store_word(UART_ADDRESS + UART_SIZE, 7);
+        assert_eq!(core.peek_word(UART_ADDRESS + UART_SIZE), 7);
+        assert_eq!(core.peek_word(UART_ADDRESS), 0);
+    }
+
+    #[test]
+    fn test_cycle_counter() {
+        let mut core = core_with_devices();
+        run_program(
+            &mut core,
+            &[
+                0x800012b7, // lui t0, 0x80001
+                0x0002a303, // lw t1, 0(t0)
+                0x0082a383, // lw t2, 8(t0)
+                0x0002a023, // sw zero, 0(t0)
+                0x0002ae03, // lw t3, 0(t0)
+                0x0042ae83, // lw t4, 4(t0)
+                0x00008067, // ret
+            ],
+        );
+        let [cycle_count, instruction_count, later_cycle_count, cycle_count_high] =
+            [6, 7, 28, 29].map(|index| core.get_int_register(index));
+        assert!(cycle_count > 0 && instruction_count > 0);
+        assert_eq!(later_cycle_count - cycle_count, 3);
+        assert_eq!(cycle_count_high, 0);
+    }
+
+    #[test]
+    fn test_sub_word_stores() {
+        let mut core = core_with_devices();
+        core.store_byte(UART_ADDRESS, b'a' as Byte);
+        core.store_byte(UART_ADDRESS + 1, b'b' as Byte);
+        core.store_half(UART_ADDRESS + 2, b'c' as Half);
+        core.store_half(UART_ADDRESS, b'd' as Half);
+        assert_eq!(core.get_output(), b"ad");
+    }
+
+    #[test]
+    fn test_sub_word_loads() {
+        let mut core = core_with_devices();
+        core.set_input(vec!["300".to_string(), "7".to_string()]);
+        // loads next to the data register do not consume input
+        assert_eq!(core.load_ubyte(UART_ADDRESS + 1), 0);
+        assert_eq!(core.load_uhalf(UART_ADDRESS + 2), 0);
+        assert_eq!(core.load_ubyte(UART_ADDRESS), 300_u32 as UByte);
+        assert_eq!(core.load_word(UART_ADDRESS), 7);
+        assert_eq!(core.load_ubyte(UART_ADDRESS + 4), 0b10);
+        assert_eq!(core.load_ubyte(UART_ADDRESS + 5), 0);
+        // sub-word loads of the counters read the addressed bytes
+        let cycle_count = core.get_cycle_count();
+        assert_eq!(
+            core.load_uhalf(CYCLE_COUNTER_ADDRESS + 2),
+            (cycle_count >> 16) as UHalf
+        );
+    }
+}
@@ -28,8 +28,8 @@ pub struct Cache {
 
 pub enum CacheAccess {
     HitSet,
-    // HitUByte(UByte),
-    // HitUHalf(UHalf),
+    HitUByte(UByte),
+    HitUHalf(UHalf),
     HitWord(Word),
     Miss,
 }
@@ -41,12 +41,12 @@ impl Cache {
             let mut map = LinkedHashMap::with_capacity(WAY_NUM);
             for _ in 0..WAY_NUM {
                 map.insert(
-                    std::u32::MAX,
+                    u32::MAX,
                     CacheLine {
                         valid: false,
                         dirty: false,
                         accessed: false,
-                        tag: std::u32::MAX,
+                        tag: u32::MAX,
                         value: [0; LINE_SIZE / WORD_SIZE],
                     },
                 );
@@ -73,6 +73,14 @@ impl Cache {
         self.offset_bit_num
     }
 
+    pub fn get_line_size(&self) -> usize {
+        1 << self.offset_bit_num
+    }
+
+    pub fn get_total_line_num(&self) -> usize {
+        self.values.len() * self.way_num
+    }
+
     fn get_tag(&self, addr: Address) -> Tag {
         addr >> (32 - self.tag_bit_num) as Tag
     }
@@ -103,40 +111,37 @@ impl Cache {
         cache_line.valid = true;
     }
 
-    // pub fn get_ubyte(&mut self, addr: Address) -> CacheAccess {
-    //     let (tag, index, offset) = self.get_status(addr);
-    //     let cache_line = self.values[index].get_refresh(&tag);
-    //     match cache_line {
-    //         Some(cache_line) => {
-    //             if !cache_line.valid {
-    //                 return CacheAccess::Miss;
-    //             }
-    //             let value = cache_line.value[offset];
-    //             Self::update_on_get(cache_line);
-    //             CacheAccess::HitUByte(value)
-    //         }
-    //         None => CacheAccess::Miss,
-    //     }
-    // }
-
-    // pub fn get_uhalf(&mut self, addr: Address) -> CacheAccess {
-    //     let (tag, index, offset) = self.get_status(addr);
-    //     let cache_line = self.values[index].get_refresh(&tag);
-    //     match cache_line {
-    //         Some(cache_line) => {
-    //             if !cache_line.valid {
-    //                 return CacheAccess::Miss;
-    //             }
-    //             let mut value: UHalf = 0;
-    //             for i in 0..2 {
-    //                 value += (cache_line.value[offset + i] as UHalf) << (8 * i);
-    //             }
-    //             Self::update_on_get(cache_line);
-    //             CacheAccess::HitUHalf(value)
-    //         }
-    //         None => CacheAccess::Miss,
-    //     }
-    // }
+    pub fn get_ubyte(&mut self, addr: Address) -> CacheAccess {
+        let (tag, index, offset) = self.get_status(addr);
+        let cache_line = self.values[index].get_refresh(&tag);
+        match cache_line {
+            Some(cache_line) => {
+                if !cache_line.valid {
+                    return CacheAccess::Miss;
+                }
+                Self::update_on_get(cache_line);
+                let value = cache_line.value[offset >> 2] >> ((offset & 3) * 8);
+                CacheAccess::HitUByte(value as UByte)
+            }
+            None => CacheAccess::Miss,
+        }
+    }
+
+    pub fn get_uhalf(&mut self, addr: Address) -> CacheAccess {
+        let (tag, index, offset) = self.get_status(addr);
+        let cache_line = self.values[index].get_refresh(&tag);
+        match cache_line {
+            Some(cache_line) => {
+                if !cache_line.valid {
+                    return CacheAccess::Miss;
+                }
+                Self::update_on_get(cache_line);
+                let value = cache_line.value[offset >> 2] >> ((offset & 2) * 8);
+                CacheAccess::HitUHalf(value as UHalf)
+            }
+            None => CacheAccess::Miss,
+        }
+    }
 
     pub fn get_word(&mut self, addr: Address) -> CacheAccess {
         let (tag, index, offset) = self.get_status(addr);
@@ -158,6 +163,49 @@ impl Cache {
         }
     }
 
+    pub fn contains_line(&self, addr: Address) -> bool {
+        let (tag, index, _) = self.get_status(addr);
+        match self.values[index].get(&tag) {
+            Some(cache_line) => cache_line.valid,
+            None => false,
+        }
+    }
+
+    /// Returns the address of the valid line that `set_line(addr, _)` would evict.
+    pub fn get_victim_line_addr(&self, addr: Address) -> Option<Address> {
+        let index = self.get_index(addr);
+        if self.values[index].len() < self.way_num {
+            return None;
+        }
+        match self.values[index].front() {
+            Some((_, cache_line)) if cache_line.valid => Some(
+                (cache_line.tag << (self.index_bit_num + self.offset_bit_num)) as Address
+                    + (index << self.offset_bit_num) as Address,
+            ),
+            _ => None,
+        }
+    }
+
+    /// Reads the cached copy of a word, if any, without changing the LRU order or the line state.
+    pub fn peek_word(&self, addr: Address) -> Option<Word> {
+        let (tag, index, offset) = self.get_status(addr);
+        match self.values[index].get(&tag) {
+            Some(cache_line) if cache_line.valid => Some(u32_to_i32(cache_line.value[offset >> 2])),
+            _ => None,
+        }
+    }
+
+    /// Updates the cached copy of a word, if any, without changing the LRU order or the line state.
+    /// Used to keep the cache coherent with writes that go directly to memory.
+    pub fn poke_word(&mut self, addr: Address, value: Word) {
+        let (tag, index, offset) = self.get_status(addr);
+        if let Some(cache_line) = self.values[index].get_mut(&tag) {
+            if cache_line.valid {
+                cache_line.value[offset >> 2] = i32_to_u32(value);
+            }
+        }
+    }
+
     pub fn set_line(
         &mut self,
         addr: Address,
@@ -202,39 +250,41 @@ impl Cache {
         }
     }
 
-    // pub fn set_ubyte(&mut self, addr: Address, value: UByte) -> CacheAccess {
-    //     let (tag, index, offset) = self.get_status(addr);
-    //     let cache_line = self.values[index].get_refresh(&tag);
-    //     match cache_line {
-    //         Some(cache_line) => {
-    //             if !cache_line.valid {
-    //                 return CacheAccess::Miss;
-    //             }
-    //             cache_line.value[offset] = value;
-    //             Self::update_on_set(cache_line);
-    //             CacheAccess::HitSet
-    //         }
-    //         None => CacheAccess::Miss,
-    //     }
-    // }
-
-    // pub fn set_uhalf(&mut self, addr: Address, value: UHalf) -> CacheAccess {
-    //     let (tag, index, offset) = self.get_status(addr);
-    //     let cache_line = self.values[index].get_refresh(&tag);
-    //     match cache_line {
-    //         Some(cache_line) => {
-    //             if !cache_line.valid {
-    //                 return CacheAccess::Miss;
-    //             }
-    //             for i in 0..2 {
-    //                 cache_line.value[offset + i] = ((value >> (i * 8)) & 0xff) as UByte;
-    //             }
-    //             Self::update_on_set(cache_line);
-    //             CacheAccess::HitSet
-    //         }
-    //         None => CacheAccess::Miss,
-    //     }
-    // }
+    pub fn set_ubyte(&mut self, addr: Address, value: UByte) -> CacheAccess {
+        let (tag, index, offset) = self.get_status(addr);
+        let cache_line = self.values[index].get_refresh(&tag);
+        match cache_line {
+            Some(cache_line) => {
+                if !cache_line.valid {
+                    return CacheAccess::Miss;
+                }
+                let shift = (offset & 3) * 8;
+                let word = &mut cache_line.value[offset >> 2];
+                *word = *word & !(0xff << shift) | (value as MemoryValue) << shift;
+                Self::update_on_set(cache_line);
+                CacheAccess::HitSet
+            }
+            None => CacheAccess::Miss,
+        }
+    }
+
+    pub fn set_uhalf(&mut self, addr: Address, value: UHalf) -> CacheAccess {
+        let (tag, index, offset) = self.get_status(addr);
+        let cache_line = self.values[index].get_refresh(&tag);
+        match cache_line {
+            Some(cache_line) => {
+                if !cache_line.valid {
+                    return CacheAccess::Miss;
+                }
+                let shift = (offset & 2) * 8;
+                let word = &mut cache_line.value[offset >> 2];
+                *word = *word & !(0xffff << shift) | (value as MemoryValue) << shift;
+                Self::update_on_set(cache_line);
+                CacheAccess::HitSet
+            }
+            None => CacheAccess::Miss,
+        }
+    }
 
     pub fn set_word(&mut self, addr: Address, value: Word) -> CacheAccess {
         let (tag, index, offset) = self.get_status(addr);
@@ -257,3 +307,36 @@ impl Cache {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sub_word_access() {
+        let mut cache = Cache::new();
+        let mut line = [0; LINE_SIZE / WORD_SIZE];
+        line[1] = 0x11223344;
+        cache.set_line(0, line);
+        assert!(matches!(cache.get_ubyte(5), CacheAccess::HitUByte(0x33)));
+        assert!(matches!(cache.get_uhalf(6), CacheAccess::HitUHalf(0x1122)));
+        cache.set_ubyte(4, 0xaa);
+        cache.set_uhalf(6, 0xbbcc);
+        assert!(
+            matches!(cache.get_word(4), CacheAccess::HitWord(v) if v == u32_to_i32(0xbbcc33aa))
+        );
+    }
+
+    #[test]
+    fn test_partial_write_makes_line_dirty() {
+        let mut cache = Cache::new();
+        let line = [0; LINE_SIZE / WORD_SIZE];
+        cache.set_line(0, line);
+        cache.set_ubyte(2, 0x7f);
+        // fill the other ways of set 0 to evict line 0
+        let set_stride = (LINE_SIZE * LINE_NUM) as Address;
+        cache.set_line(set_stride, line);
+        let evicted = cache.set_line(2 * set_stride, line).unwrap();
+        assert_eq!(evicted[0], (0, 0x007f0000));
+    }
+}
@@ -0,0 +1,372 @@
+use crate::decoder::*;
+use crate::instruction::*;
+use crate::isa::*;
+use crate::types::*;
+use crate::utils::*;
+
+/// In the C extension, instructions whose lowest two bits are not 0b11 are 16 bits long.
+pub fn is_compressed(inst: InstructionValue) -> bool {
+    inst & 0b11 != 0b11
+}
+
+/// Bits `high..=low` of `inst`, shifted down to bit 0.
+fn bits(inst: InstructionValue, high: u32, low: u32) -> u32 {
+    (inst >> low) & ((1 << (high - low + 1)) - 1)
+}
+
+/// A signed immediate in the 12-bit field layout of `decode_instruction`.
+fn imm12(value: i32) -> Imm12 {
+    (value & 0xfff) as Imm12
+}
+
+/// x8 to x15 (or f8 to f15), the registers of the 3-bit register fields.
+fn compact_register(field: u32) -> u8 {
+    (field + 8) as u8
+}
+
+/// Expands the 16-bit instruction in the lower half of `inst` to the RV32FC instruction it
+/// stands for, with the immediates laid out as `decode_instruction` returns them.
+/// The illegal all-zero instruction, reserved encodings and the RV64 and D extension
+/// instructions give `Instruction::Other`.
+pub fn expand_compressed_instruction(inst: InstructionValue) -> Instruction {
+    let funct3 = bits(inst, 15, 13);
+    let rd = bits(inst, 11, 7) as Rd;
+    let rs2 = bits(inst, 6, 2) as Rs2;
+    let rd_compact = compact_register(bits(inst, 4, 2));
+    let rs1_compact = compact_register(bits(inst, 9, 7));
+    let imm6 = sign_extention_i32((bits(inst, 12, 12) << 5 | bits(inst, 6, 2)) as i32, 6);
+    let shamt = (bits(inst, 12, 12) << 5 | bits(inst, 6, 2)) as Imm12;
+    // offsets of c.lw and c.sw, and of c.lwsp and c.swsp
+    let word_offset =
+        (bits(inst, 12, 10) << 3 | bits(inst, 6, 6) << 2 | bits(inst, 5, 5) << 6) as Imm12;
+    let sp_load_offset =
+        (bits(inst, 12, 12) << 5 | bits(inst, 6, 4) << 2 | bits(inst, 3, 2) << 6) as Imm12;
+    let sp_store_offset = (bits(inst, 12, 9) << 2 | bits(inst, 8, 7) << 6) as Imm12;
+    let jump_offset = sign_extention_i32(
+        (bits(inst, 12, 12) << 11
+            | bits(inst, 11, 11) << 4
+            | bits(inst, 10, 9) << 8
+            | bits(inst, 8, 8) << 10
+            | bits(inst, 7, 7) << 6
+            | bits(inst, 6, 6) << 7
+            | bits(inst, 5, 3) << 1
+            | bits(inst, 2, 2) << 5) as i32,
+        12,
+    );
+    let branch_offset = sign_extention_i32(
+        (bits(inst, 12, 12) << 8
+            | bits(inst, 11, 10) << 3
+            | bits(inst, 6, 5) << 6
+            | bits(inst, 4, 3) << 1
+            | bits(inst, 2, 2) << 5) as i32,
+        9,
+    );
+    match (bits(inst, 1, 0), funct3) {
+        // c.addi4spn
+        (0b00, 0b000) => {
+            let imm = bits(inst, 12, 11) << 4
+                | bits(inst, 10, 7) << 6
+                | bits(inst, 6, 6) << 2
+                | bits(inst, 5, 5) << 3;
+            if imm == 0 {
+                return Instruction::Other;
+            }
+            Instruction::I(imm as Imm12, SP as Rs1, 0b000, rd_compact, 19)
+        }
+        // c.lw and c.flw
+        (0b00, 0b010) => Instruction::I(word_offset, rs1_compact, 0b010, rd_compact, 3),
+        (0b00, 0b011) => Instruction::I(word_offset, rs1_compact, 0b010, rd_compact, 7),
+        // c.sw and c.fsw
+        (0b00, 0b110) => Instruction::S(word_offset, rd_compact, rs1_compact, 0b010, 35),
+        (0b00, 0b111) => Instruction::S(word_offset, rd_compact, rs1_compact, 0b010, 39),
+        // c.addi (c.nop for x0)
+        (0b01, 0b000) => Instruction::I(imm12(imm6), rd, 0b000, rd, 19),
+        // c.jal and c.j
+        (0b01, 0b001) => Instruction::J((jump_offset >> 1) & 0xfffff, RA as Rd, 111),
+        (0b01, 0b101) => Instruction::J((jump_offset >> 1) & 0xfffff, ZERO as Rd, 111),
+        // c.li
+        (0b01, 0b010) => Instruction::I(imm12(imm6), ZERO as Rs1, 0b000, rd, 19),
+        // c.addi16sp
+        (0b01, 0b011) if rd == SP as Rd => {
+            let imm = sign_extention_i32(
+                (bits(inst, 12, 12) << 9
+                    | bits(inst, 6, 6) << 4
+                    | bits(inst, 5, 5) << 6
+                    | bits(inst, 4, 3) << 7
+                    | bits(inst, 2, 2) << 5) as i32,
+                10,
+            );
+            if imm == 0 {
+                return Instruction::Other;
+            }
+            Instruction::I(imm12(imm), rd, 0b000, rd, 19)
+        }
+        // c.lui
+        (0b01, 0b011) => {
+            if imm6 == 0 {
+                return Instruction::Other;
+            }
+            Instruction::U(imm6 & 0xfffff, rd, 55)
+        }
+        (0b01, 0b100) => match bits(inst, 11, 10) {
+            // shift amounts of 32 and more are for RV64
+            0b00 | 0b01 if shamt >= 32 => Instruction::Other,
+            // c.srli and c.srai
+            0b00 => Instruction::I(shamt, rs1_compact, 0b101, rs1_compact, 19),
+            0b01 => Instruction::I(0b0100000 << 5 | shamt, rs1_compact, 0b101, rs1_compact, 19),
+            // c.andi
+            0b10 => Instruction::I(imm12(imm6), rs1_compact, 0b111, rs1_compact, 19),
+            // c.subw and c.addw are for RV64
+            _ if bits(inst, 12, 12) == 1 => Instruction::Other,
+            // c.sub, c.xor, c.or and c.and
+            _ => {
+                let (funct7, funct3) = match bits(inst, 6, 5) {
+                    0b00 => (0b0100000, 0b000),
+                    0b01 => (0b0000000, 0b100),
+                    0b10 => (0b0000000, 0b110),
+                    _ => (0b0000000, 0b111),
+                };
+                Instruction::R(funct7, rd_compact, rs1_compact, funct3, rs1_compact, 51)
+            }
+        },
+        // c.beqz and c.bnez
+        (0b01, 0b110) => Instruction::B(imm12(branch_offset >> 1), 0, rs1_compact, 0b000, 99),
+        (0b01, 0b111) => Instruction::B(imm12(branch_offset >> 1), 0, rs1_compact, 0b001, 99),
+        // c.slli
+        (0b10, 0b000) if shamt < 32 => Instruction::I(shamt, rd, 0b001, rd, 19),
+        // c.lwsp and c.flwsp
+        (0b10, 0b010) if rd != 0 => Instruction::I(sp_load_offset, SP as Rs1, 0b010, rd, 3),
+        (0b10, 0b011) => Instruction::I(sp_load_offset, SP as Rs1, 0b010, rd, 7),
+        (0b10, 0b100) => match (bits(inst, 12, 12), rd, rs2) {
+            (0, 0, 0) => Instruction::Other,
+            // c.jr and c.mv
+            (0, _, 0) => Instruction::I(0, rd, 0b000, ZERO as Rd, 103),
+            (0, _, _) => Instruction::R(0, rs2, ZERO as Rs1, 0b000, rd, 51),
+            // c.ebreak, c.jalr and c.add
+            (_, 0, 0) => Instruction::I(1, 0, 0b000, 0, 115),
+            (_, _, 0) => Instruction::I(0, rd, 0b000, RA as Rd, 103),
+            _ => Instruction::R(0, rs2, rd, 0b000, rd, 51),
+        },
+        // c.swsp and c.fswsp
+        (0b10, 0b110) => Instruction::S(sp_store_offset, rs2, SP as Rs1, 0b010, 35),
+        (0b10, 0b111) => Instruction::S(sp_store_offset, rs2, SP as Rs1, 0b010, 39),
+        _ => Instruction::Other,
+    }
+}
+
+/// Tells whether a 32-bit instruction has a 16-bit form in RV32FC, which is how much of
+/// a program an assembler could compress. Operations that commute and `addi rd, rs1, 0`
+/// (`mv`) are matched like assemblers do.
+pub fn has_compressed_form(def: &InstructionDef, operands: &Operands) -> bool {
+    let Operands {
+        rd, rs1, rs2, imm, ..
+    } = *operands;
+    let (zero, ra, sp) = (ZERO as u8, RA as u8, SP as u8);
+    let compact = |register: u8| (8..16).contains(&register);
+    let fits = |value: i32, width: u32| -(1 << (width - 1)) <= value && value < 1 << (width - 1);
+    // offsets of c.lw and c.sw, and of c.lwsp and c.swsp
+    let word_offset = |limit: i32| imm % 4 == 0 && (0..limit).contains(&imm);
+    match (def.op, def.funct3, def.funct7) {
+        // c.addi, c.nop, c.li, c.mv, c.addi16sp and c.addi4spn
+        (19, Some(0b000), _) => {
+            (rd != zero && rd == rs1 && imm != 0 && fits(imm, 6))
+                || (rd == zero && rs1 == zero && imm == 0)
+                || (rd != zero && rs1 == zero && fits(imm, 6))
+                || (rd != zero && rs1 != zero && imm == 0)
+                || (rd == sp && rs1 == sp && imm != 0 && imm % 16 == 0 && fits(imm, 10))
+                || (compact(rd) && rs1 == sp && imm > 0 && imm % 4 == 0 && imm < 1024)
+        }
+        // c.slli
+        (19, Some(0b001), _) => rd != zero && rd == rs1 && imm & 0x1f != 0,
+        // c.srli and c.srai
+        (19, Some(0b101), _) => compact(rd) && rd == rs1 && imm & 0x1f != 0,
+        // c.andi
+        (19, Some(0b111), _) => compact(rd) && rd == rs1 && fits(imm, 6),
+        // c.add and c.mv
+        (51, Some(0b000), Some(0b0000000)) => {
+            rd != zero
+                && (((rs1 == rd || rs1 == zero) && rs2 != zero)
+                    || ((rs2 == rd || rs2 == zero) && rs1 != zero))
+        }
+        // c.sub
+        (51, Some(0b000), Some(0b0100000)) => compact(rd) && rd == rs1 && compact(rs2),
+        // c.xor, c.or and c.and
+        (51, Some(0b100 | 0b110 | 0b111), Some(0b0000000)) => {
+            compact(rs1) && compact(rs2) && (rd == rs1 || rd == rs2)
+        }
+        // c.lw, c.lwsp, c.flw and c.flwsp
+        (3 | 7, Some(0b010), _) => {
+            (rs1 == sp && (rd != zero || def.op == 7) && word_offset(256))
+                || (compact(rd) && compact(rs1) && word_offset(128))
+        }
+        // c.sw, c.swsp, c.fsw and c.fswsp
+        (35 | 39, Some(0b010), _) => {
+            (rs1 == sp && word_offset(256)) || (compact(rs1) && compact(rs2) && word_offset(128))
+        }
+        // c.j and c.jal, with the offset divided by 2
+        (111, _, _) => (rd == zero || rd == ra) && fits(imm, 11),
+        // c.jr and c.jalr
+        (103, Some(0b000), _) => (rd == zero || rd == ra) && rs1 != zero && imm == 0,
+        // c.beqz and c.bnez, with the offset divided by 2
+        (99, Some(0b000 | 0b001), _) => {
+            fits(imm, 8) && ((rs2 == zero && compact(rs1)) || (rs1 == zero && compact(rs2)))
+        }
+        // c.ebreak
+        (115, Some(0b000), _) => imm == 1 && rs1 == zero && rd == zero,
+        // c.lui
+        (55, _, _) => {
+            let upper = sign_extention_i32(imm & 0xfffff, 20);
+            rd != zero && rd != sp && upper != 0 && fits(upper, 6)
+        }
+        _ => false,
+    }
+}
+
+/// Instruction counts for the code size report. Compressible instructions are the 32-bit
+/// ones that have a 16-bit form, and the sizes with them compressed tell how much the
+/// C extension would shrink the program.
+#[derive(Default)]
+pub struct CodeSizeStats {
+    instruction_count: u64,
+    compressed_count: u64,
+    compressible_count: u64,
+}
+
+impl CodeSizeStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, inst: &InstructionStruct) {
+        self.instruction_count += 1;
+        if get_length(inst) == 2 {
+            self.compressed_count += 1;
+        } else if has_compressed_form(get_def(inst), get_operands(inst)) {
+            self.compressible_count += 1;
+        }
+    }
+
+    /// Prints the stats for the instructions in the program (static) or the executed
+    /// instructions (dynamic), with sizes relative to encoding every instruction in 32 bits.
+    pub fn show(&self, kind: &str) {
+        let full_size = self.instruction_count * 4;
+        let size = full_size - self.compressed_count * 2;
+        let compressed_size = size - self.compressible_count * 2;
+        let saving = |size: u64| {
+            if full_size == 0 {
+                0.0
+            } else {
+                (full_size - size) as f64 / full_size as f64 * 100.0
+            }
+        };
+        println!("{} instruction count: {}", kind, self.instruction_count);
+        println!(
+            "{} compressed instruction count: {}",
+            kind, self.compressed_count
+        );
+        println!(
+            "{} compressible instruction count: {}",
+            kind, self.compressible_count
+        );
+        println!(
+            "{} code size: {} bytes ({:.2}% smaller than 32-bit only)",
+            kind,
+            size,
+            saving(size)
+        );
+        println!(
+            "{} code size with compressible instructions compressed: {} bytes ({:.2}% smaller)",
+            kind,
+            compressed_size,
+            saving(compressed_size)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RVC instructions and their expansions, from the LLVM assembler.
+    const EXPANSIONS: [(InstructionValue, InstructionValue); 35] = [
+        (0x0040, 0x00410413), // c.addi4spn s0, sp, 4
+        (0x4080, 0x0004a403), // c.lw s0, 0(s1)
+        (0x7c7c, 0x07c42787), // c.flw fa5, 124(s0)
+        (0xc0c0, 0x0084a223), // c.sw s0, 4(s1)
+        (0xe3a8, 0x04a7a027), // c.fsw fa0, 64(a5)
+        (0x0001, 0x00000013), // c.nop
+        (0x157d, 0xfff50513), // c.addi a0, -1
+        (0x3ffd, 0xfffff0ef), // c.jal -2
+        (0x4515, 0x00500513), // c.li a0, 5
+        (0x7179, 0xfd010113), // c.addi16sp sp, -48
+        (0x617d, 0x1f010113), // c.addi16sp sp, 496
+        (0x75fd, 0xfffff5b7), // c.lui a1, 0xfffff
+        (0x65fd, 0x0001f5b7), // c.lui a1, 0x1f
+        (0x807d, 0x01f45413), // c.srli s0, 31
+        (0x8405, 0x40145413), // c.srai s0, 1
+        (0x987d, 0xfff47413), // c.andi s0, -1
+        (0x8c05, 0x40940433), // c.sub s0, s1
+        (0x8fb9, 0x00e7c7b3), // c.xor a5, a4
+        (0x8d4d, 0x00b56533), // c.or a0, a1
+        (0x8c65, 0x00947433), // c.and s0, s1
+        (0xa001, 0x0000006f), // c.j 0
+        (0xb001, 0x801ff06f), // c.j -2048
+        (0xdc7d, 0xfe040fe3), // c.beqz s0, -2
+        (0xeffd, 0x0e079f63), // c.bnez a5, 254
+        (0x050a, 0x00251513), // c.slli a0, 2
+        (0x4502, 0x00012503), // c.lwsp a0, 0(sp)
+        (0x50fe, 0x0fc12083), // c.lwsp ra, 252(sp)
+        (0x6532, 0x00c12507), // c.flwsp fa0, 12(sp)
+        (0x8082, 0x00008067), // c.jr ra
+        (0x852e, 0x00b00533), // c.mv a0, a1
+        (0x9002, 0x00100073), // c.ebreak
+        (0x9582, 0x000580e7), // c.jalr a1
+        (0x952e, 0x00b50533), // c.add a0, a1
+        (0xc606, 0x00112623), // c.swsp ra, 12(sp)
+        (0xfeae, 0x06b12e27), // c.fswsp fa1, 124(sp)
+    ];
+
+    #[test]
+    fn test_expand_compressed_instruction() {
+        for (compressed, expanded) in EXPANSIONS {
+            assert!(is_compressed(compressed) && !is_compressed(expanded));
+            assert_eq!(
+                format!("{:?}", expand_compressed_instruction(compressed)),
+                format!("{:?}", decode_instruction(expanded)),
+                "{:#06x}",
+                compressed
+            );
+        }
+        // illegal, c.fld, c.addi16sp and c.lui with 0, c.lwsp and c.jr with x0, c.subw and
+        // c.slli by 32
+        for inst in [
+            0x0000, 0x2000, 0x6101, 0x6001, 0x4002, 0x8002, 0x9c21, 0x1006,
+        ] {
+            assert!(matches!(
+                expand_compressed_instruction(inst),
+                Instruction::Other
+            ));
+        }
+    }
+
+    #[test]
+    fn test_has_compressed_form() {
+        let instruction_set = InstructionSet::new(Isa::Rv32imf);
+        let has_compressed_form = |inst: InstructionValue| {
+            let inst = instruction_set.create_instruction_struct(decode_instruction(inst));
+            super::has_compressed_form(get_def(&inst), get_operands(&inst))
+        };
+        for (_, expanded) in EXPANSIONS {
+            assert!(has_compressed_form(expanded), "{:#010x}", expanded);
+        }
+        assert!(has_compressed_form(0x00058513)); // addi a0, a1, 0
+        assert!(has_compressed_form(0x0084f433)); // and s0, s1, s0
+        assert!(!has_compressed_form(0x00a4f5b3)); // and a1, s1, a0
+        assert!(!has_compressed_form(0x00410813)); // addi a6, sp, 4
+        assert!(!has_compressed_form(0x10012503)); // lw a0, 256(sp)
+        assert!(!has_compressed_form(0x0804a403)); // lw s0, 128(s1)
+        assert!(!has_compressed_form(0x000200b7)); // lui ra, 0x20
+        assert!(!has_compressed_form(0x00c58533)); // add a0, a1, a2
+        assert!(!has_compressed_form(0x0000106f)); // j 4096
+    }
+}
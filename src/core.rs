@@ -6,29 +6,72 @@ use std::thread;
 use std::time::Duration;
 use std::time::Instant;
 
+use crate::bus::*;
 use crate::cache::*;
+use crate::compressed::*;
+use crate::csr::*;
 use crate::decoder::*;
 use crate::fpu_emulator::*;
+use crate::heatmap::*;
 use crate::instruction::*;
 use crate::instruction_memory::*;
+use crate::isa::*;
 use crate::memory::*;
+use crate::memory_check::*;
+use crate::memory_dump::*;
+use crate::memory_trace::*;
+use crate::miss_classifier::*;
+use crate::mshr::*;
+use crate::prefetcher::*;
 use crate::register::*;
 use crate::sld_loader::*;
+use crate::stack_distance::*;
+use crate::stack_monitor::*;
 use crate::types::*;
+use crate::uninit_checker::*;
 use crate::utils::*;
 
 const INT_REGISTER_SIZE: usize = 32;
 const FLOAT_REGISTER_SIZE: usize = 32;
-// const IO_ADDRESS: Address = 2147483648;
+const DEFAULT_CLOCK_FREQUENCY: u64 = 100_000_000;
 
 pub struct Core {
     memory: Memory,
     cache: Cache,
+    bus: Option<Bus>,
     memory_access_count: usize,
     cache_hit_count: usize,
+    memory_check: MemoryCheck,
+    memory_fault_count: usize,
+    mapped_ranges: Vec<(u64, u64)>,
+    miss_classifier: Option<MissClassifier>,
+    miss_stats_top_num: usize,
+    memory_tracer: Option<MemoryTracer>,
+    memory_dumper: Option<MemoryDumper>,
+    prefetcher: Option<Prefetcher>,
+    stack_distance_analyzer: Option<(StackDistanceAnalyzer, String)>,
+    heatmap: Option<(Heatmap, String)>,
+    mshr_timing_model: Option<MshrTimingModel>,
+    stack_monitor: Option<StackMonitor>,
+    uninit_checker: Option<UninitChecker>,
     instruction_memory: InstructionMemory,
+    unified_memory: bool,
+    mul_latency: Cycle,
+    div_latency: Cycle,
+    mul_div_stall_cycles: Cycle,
+    fma_mode: FmaMode,
     instruction_memory_access_count: usize,
     instruction_count: InstructionCount,
+    cycle_count: Cycle,
+    halted: bool,
+    halted_in_exec_stage: bool,
+    system_call_in_progress: bool,
+    exit_code: Option<Int>,
+    breakpoint_pc: Option<Address>,
+    heap_start: Address,
+    program_break: Address,
+    clock_frequency: u64,
+    csr_file: CsrFile,
     int_registers: [IntRegister; INT_REGISTER_SIZE],
     float_registers: [FloatRegister; FLOAT_REGISTER_SIZE],
     pc: Address,
@@ -38,11 +81,18 @@ pub struct Core {
     pc_history: Vec<Address>,
     pc_stats: HashMap<Address, (Instruction, usize)>,
     inst_stats: HashMap<String, usize>,
+    code_size_stats: Option<CodeSizeStats>,
+    instruction_set: InstructionSet,
     fetched_instruction: Option<InstructionValue>,
-    decoded_instruction: Option<InstructionEnum>,
-    instruction_in_exec_stage: Option<InstructionEnum>,
-    instruction_in_memory_stage: Option<InstructionEnum>,
-    instruction_in_write_back_stage: Option<InstructionEnum>,
+    decoded_instruction: Option<InstructionStruct>,
+    instruction_in_exec_stage: Option<InstructionStruct>,
+    instruction_in_memory_stage: Option<InstructionStruct>,
+    instruction_in_write_back_stage: Option<InstructionStruct>,
+    fetched_pc: Option<Address>,
+    decoded_pc: Option<Address>,
+    exec_stage_pc: Option<Address>,
+    memory_stage_pc: Option<Address>,
+    write_back_stage_pc: Option<Address>,
     forwarding_int_sources: [Option<(InstructionCount, Int)>; 32],
     forwarding_float_sources: [Option<(InstructionCount, FloatingPoint)>; 32],
     inv_map: InvMap,
@@ -56,11 +106,40 @@ impl Core {
     pub fn new() -> Self {
         let memory = Memory::new();
         let cache = Cache::new();
+        let bus = None;
         let memory_access_count = 0;
         let cache_hit_count = 0;
+        let memory_check = MemoryCheck::Ignore;
+        let memory_fault_count = 0;
+        let mapped_ranges = default_mapped_ranges();
+        let miss_classifier = None;
+        let miss_stats_top_num = 0;
+        let memory_tracer = None;
+        let memory_dumper = None;
+        let prefetcher = None;
+        let stack_distance_analyzer = None;
+        let heatmap = None;
+        let mshr_timing_model = None;
+        let stack_monitor = None;
+        let uninit_checker = None;
         let instruction_memory = InstructionMemory::new();
+        let unified_memory = false;
+        let mul_latency = 1;
+        let div_latency = 1;
+        let mul_div_stall_cycles = 0;
+        let fma_mode = FmaMode::Unfused;
         let instruction_memory_access_count = 0;
         let instruction_count = 0;
+        let cycle_count = 0;
+        let halted = false;
+        let halted_in_exec_stage = false;
+        let system_call_in_progress = false;
+        let exit_code = None;
+        let breakpoint_pc = None;
+        let heap_start = 0;
+        let program_break = 0;
+        let clock_frequency = DEFAULT_CLOCK_FREQUENCY;
+        let csr_file = CsrFile::new();
         let int_registers = [IntRegister::new(); INT_REGISTER_SIZE];
         let float_registers = [FloatRegister::new(); FLOAT_REGISTER_SIZE];
         let pc = 0;
@@ -70,11 +149,18 @@ impl Core {
         let instruction_count_history = Vec::new();
         let pc_stats = HashMap::new();
         let inst_stats = HashMap::new();
+        let code_size_stats = None;
+        let instruction_set = InstructionSet::new(Isa::Cpuex2);
         let fetched_instruction = None;
         let decoded_instruction = None;
         let instruction_in_exec_stage = None;
         let instruction_in_memory_stage = None;
         let instruction_in_write_back_stage = None;
+        let fetched_pc = None;
+        let decoded_pc = None;
+        let exec_stage_pc = None;
+        let memory_stage_pc = None;
+        let write_back_stage_pc = None;
         let forwarding_int_sources = [None; 32];
         let forwarding_float_sources = [None; 32];
         let inv_map = create_inv_map();
@@ -85,11 +171,40 @@ impl Core {
         Core {
             memory,
             cache,
+            bus,
             memory_access_count,
             cache_hit_count,
+            memory_check,
+            memory_fault_count,
+            mapped_ranges,
+            miss_classifier,
+            miss_stats_top_num,
+            memory_tracer,
+            memory_dumper,
+            prefetcher,
+            stack_distance_analyzer,
+            heatmap,
+            mshr_timing_model,
+            stack_monitor,
+            uninit_checker,
             instruction_memory,
+            unified_memory,
+            mul_latency,
+            div_latency,
+            mul_div_stall_cycles,
+            fma_mode,
             instruction_memory_access_count,
             instruction_count,
+            cycle_count,
+            halted,
+            halted_in_exec_stage,
+            system_call_in_progress,
+            exit_code,
+            breakpoint_pc,
+            heap_start,
+            program_break,
+            clock_frequency,
+            csr_file,
             int_registers,
             float_registers,
             pc,
@@ -99,11 +214,18 @@ impl Core {
             instruction_count_history,
             pc_stats,
             inst_stats,
+            code_size_stats,
+            instruction_set,
             fetched_instruction,
             decoded_instruction,
             instruction_in_exec_stage,
             instruction_in_memory_stage,
             instruction_in_write_back_stage,
+            fetched_pc,
+            decoded_pc,
+            exec_stage_pc,
+            memory_stage_pc,
+            write_back_stage_pc,
             forwarding_int_sources,
             forwarding_float_sources,
             inv_map,
@@ -114,37 +236,38 @@ impl Core {
         }
     }
 
-    pub fn get_decoded_instruction(&self) -> &Option<InstructionEnum> {
+    pub fn get_decoded_instruction(&self) -> &Option<InstructionStruct> {
         &self.decoded_instruction
     }
 
-    pub fn set_decoded_instruction(&mut self, inst: Option<InstructionEnum>) {
+    pub fn set_decoded_instruction(&mut self, inst: Option<InstructionStruct>) {
         self.decoded_instruction = inst;
     }
 
-    pub fn get_instruction_in_exec_stage(&self) -> &Option<InstructionEnum> {
+    pub fn get_instruction_in_exec_stage(&self) -> &Option<InstructionStruct> {
         &self.instruction_in_exec_stage
     }
 
-    pub fn set_instruction_in_exec_stage(&mut self, inst: Option<InstructionEnum>) {
+    pub fn set_instruction_in_exec_stage(&mut self, inst: Option<InstructionStruct>) {
         self.instruction_in_exec_stage = inst;
     }
 
-    pub fn get_instruction_in_memory_stage(&self) -> &Option<InstructionEnum> {
+    pub fn get_instruction_in_memory_stage(&self) -> &Option<InstructionStruct> {
         &self.instruction_in_memory_stage
     }
 
-    pub fn set_instruction_in_memory_stage(&mut self, inst: Option<InstructionEnum>) {
+    pub fn set_instruction_in_memory_stage(&mut self, inst: Option<InstructionStruct>) {
         self.instruction_in_memory_stage = inst;
     }
 
-    pub fn get_instruction_in_write_back_stage(&self) -> &Option<InstructionEnum> {
+    pub fn get_instruction_in_write_back_stage(&self) -> &Option<InstructionStruct> {
         &self.instruction_in_write_back_stage
     }
 
     fn fetch_instruction(&mut self) {
         let current_pc = self.get_pc();
         self.fetched_instruction = Some(self.load_instruction(current_pc));
+        self.fetched_pc = Some(current_pc);
     }
 
     pub fn get_inv_map(&self) -> &InvMap {
@@ -159,8 +282,12 @@ impl Core {
         self.pc
     }
 
+    /// Advances the pc past the fetched instruction.
     pub fn increment_pc(&mut self) {
-        self.pc += 4;
+        self.pc += match self.fetched_instruction {
+            Some(inst) => self.instruction_set.get_instruction_length(inst),
+            None => 4,
+        };
     }
 
     pub fn set_pc(&mut self, new_pc: Address) {
@@ -175,6 +302,8 @@ impl Core {
                 // flush instruction in IF and ID stage
                 self.fetched_instruction = None;
                 self.decoded_instruction = None;
+                self.fetched_pc = None;
+                self.decoded_pc = None;
             } else if !stalling {
                 self.increment_pc();
             }
@@ -191,13 +320,78 @@ impl Core {
         self.instruction_count += 1;
     }
 
+    /// Makes instructions and data share one address space. The program is loaded into
+    /// data memory, and fetch reads it with `peek_word`, which sees dirty lines of the data
+    /// cache but is not counted as a cache access. Stores to code are visible to fetch
+    /// (instructions already in the pipeline are not refetched).
+    pub fn enable_unified_memory(&mut self) {
+        self.unified_memory = true;
+    }
+
     pub fn load_instruction(&mut self, addr: Address) -> InstructionValue {
         self.increment_instruction_memory_access_count();
-        self.instruction_memory.load(addr)
+        self.peek_instruction(addr)
+    }
+
+    /// Reads an instruction without counting an access. At an address that is not a
+    /// multiple of 4, which only compressed code jumps to, the instruction is read from the
+    /// upper half of one word and, unless it is compressed, the lower half of the next.
+    fn peek_instruction(&self, addr: Address) -> InstructionValue {
+        if addr.is_multiple_of(4) {
+            return self.peek_instruction_word(addr);
+        }
+        let lower = self.peek_instruction_word(addr - 2) >> 16;
+        if is_compressed(lower) {
+            lower
+        } else {
+            lower | self.peek_instruction_word(addr + 2) << 16
+        }
+    }
+
+    fn peek_instruction_word(&self, addr: Address) -> InstructionValue {
+        if self.unified_memory {
+            i32_to_u32(self.peek_word(addr))
+        } else {
+            self.instruction_memory.load(addr)
+        }
+    }
+
+    /// Decodes the instruction whose lowest bits are in `inst`, or returns `None` if it is
+    /// not an instruction.
+    fn decode(&self, inst: InstructionValue) -> Option<InstructionStruct> {
+        let decoded = self.instruction_set.decode_instruction(inst);
+        if let Instruction::Other = decoded {
+            return None;
+        }
+        let mut inst_struct = self.instruction_set.create_instruction_struct(decoded);
+        inst_struct.set_length(self.instruction_set.get_instruction_length(inst));
+        Some(inst_struct)
+    }
+
+    /// Decodes the program from address 0 up to the first word that is not an instruction,
+    /// like the .pc file.
+    fn decode_program(&self) -> Vec<(Address, InstructionStruct)> {
+        let mut program = vec![];
+        let mut pc = 0;
+        while let Some(inst) = self.decode(self.peek_instruction(pc)) {
+            let length = get_length(&inst);
+            program.push((pc, inst));
+            pc += length;
+        }
+        program
     }
 
     pub fn store_instruction(&mut self, addr: Address, inst: InstructionValue) {
-        self.instruction_memory.store(addr, inst);
+        if self.unified_memory {
+            self.extend_program_break(addr + 4);
+            self.add_mapped_range(addr as u64, addr as u64 + 4);
+            self.memory.store_word(addr, u32_to_i32(inst));
+            if let Some(uninit_checker) = &mut self.uninit_checker {
+                uninit_checker.mark_word(addr);
+            }
+        } else {
+            self.instruction_memory.store(addr, inst);
+        }
     }
 
     pub fn get_int_register(&self, index: usize) -> Int {
@@ -209,6 +403,43 @@ impl Core {
             return; // zero register
         }
         self.int_registers[index].set(value);
+        if let Some(uninit_checker) = &mut self.uninit_checker {
+            uninit_checker.mark_register(&RegisterId::Int(index as u8));
+        }
+        if let Some(stack_monitor) = &mut self.stack_monitor {
+            let heap_register = stack_monitor.get_heap_register();
+            if index == SP || index == heap_register {
+                let sp = i32_to_u32(self.int_registers[SP].get());
+                let hp = i32_to_u32(self.int_registers[heap_register].get());
+                let pc = self.write_back_stage_pc.unwrap_or(0);
+                let inst_count = self
+                    .instruction_in_write_back_stage
+                    .as_ref()
+                    .and_then(get_instruction_count)
+                    .unwrap_or(self.instruction_count);
+                if stack_monitor.update(sp, hp, pc, inst_count) {
+                    self.halted = true;
+                }
+            }
+        }
+    }
+
+    pub fn set_stack_monitor(
+        &mut self,
+        mode: StackMonitorMode,
+        heap_register: usize,
+        log_file_path: Option<&str>,
+    ) -> std::io::Result<()> {
+        let sp = i32_to_u32(self.get_int_register(SP));
+        let hp = i32_to_u32(self.get_int_register(heap_register));
+        self.stack_monitor = Some(StackMonitor::new(
+            mode,
+            heap_register,
+            sp,
+            hp,
+            log_file_path,
+        )?);
+        Ok(())
     }
 
     pub fn get_float_register(&self, index: usize) -> FloatingPoint {
@@ -217,6 +448,9 @@ impl Core {
 
     pub fn set_float_register(&mut self, index: usize, value: FloatingPoint) {
         self.float_registers[index].set(value);
+        if let Some(uninit_checker) = &mut self.uninit_checker {
+            uninit_checker.mark_register(&RegisterId::Float(index as u8));
+        }
     }
 
     fn increment_memory_access_count(&mut self) {
@@ -227,6 +461,308 @@ impl Core {
         self.cache_hit_count += 1;
     }
 
+    pub fn set_memory_check(&mut self, memory_check: MemoryCheck) {
+        self.memory_check = memory_check;
+    }
+
+    /// Adds `[start, end)` to the ranges that checked accesses may touch. A range that
+    /// overlaps or adjoins an existing one extends it.
+    pub fn add_mapped_range(&mut self, start: u64, end: u64) {
+        for range in &mut self.mapped_ranges {
+            if range.0 <= end && start <= range.1 {
+                *range = (range.0.min(start), range.1.max(end));
+                return;
+            }
+        }
+        self.mapped_ranges.push((start, end));
+    }
+
+    /// Makes the following data accesses belong to the `ecall` in the exec stage, whose
+    /// system call accesses memory, until `end_system_call` is called.
+    pub fn begin_system_call(&mut self) {
+        self.system_call_in_progress = true;
+    }
+
+    pub fn end_system_call(&mut self) {
+        self.system_call_in_progress = false;
+    }
+
+    /// Returns the pc and the instruction that data accesses belong to: the instruction in the
+    /// memory stage, or the `ecall` in the exec stage during a system call.
+    fn get_accessing_instruction(&self) -> (Address, &Option<InstructionStruct>) {
+        if self.system_call_in_progress {
+            (
+                self.exec_stage_pc.unwrap_or(0),
+                &self.instruction_in_exec_stage,
+            )
+        } else {
+            (
+                self.memory_stage_pc.unwrap_or(0),
+                &self.instruction_in_memory_stage,
+            )
+        }
+    }
+
+    /// Checks a data access of the accessing instruction and reports a fault.
+    /// Returns false if the access must not be performed.
+    fn check_memory_access(&mut self, addr: Address, size: usize, is_write: bool) -> bool {
+        if self.memory_check == MemoryCheck::Ignore {
+            return true;
+        }
+        let fault = match check_access(addr, size, &self.mapped_ranges) {
+            Some(fault) => fault,
+            None => return true,
+        };
+        self.memory_fault_count += 1;
+        let (pc, inst) = self.get_accessing_instruction();
+        let mut inst_count = self.instruction_count;
+        let (inst_string, base_register) = match inst {
+            // the buffers of system calls are arguments, not base registers
+            Some(inst) if self.system_call_in_progress => {
+                inst_count = get_instruction_count(inst).unwrap_or(inst_count);
+                (format!("{:?}", inst), None)
+            }
+            Some(inst) => {
+                inst_count = get_instruction_count(inst).unwrap_or(inst_count);
+                let base_register =
+                    get_source_registers(inst)
+                        .into_iter()
+                        .find_map(|rs| match rs {
+                            RegisterId::Int(rs) => Some(rs),
+                            RegisterId::Float(_) => None,
+                        });
+                (format!("{:?}", inst), base_register)
+            }
+            None => ("?".to_string(), None),
+        };
+        eprintln!(
+            "\n{} memory fault: {} {} at {:#010x}",
+            if self.memory_check == MemoryCheck::Trap {
+                "error:"
+            } else {
+                "warning:"
+            },
+            fault,
+            if is_write { "store" } else { "load" },
+            addr
+        );
+        eprint!("    pc: {} ({})", pc, inst_string);
+        // older instructions have been written back, so the register file holds the base value
+        if let Some(rs) = base_register {
+            let base_value = self.get_int_register(rs as usize);
+            eprint!(", base: x{} = {:#010x}", rs, i32_to_u32(base_value));
+        }
+        eprintln!(", inst_count: {}", inst_count);
+        if self.memory_check == MemoryCheck::Trap {
+            if self.system_call_in_progress {
+                self.halt();
+            } else {
+                // the faulting instruction is not written back
+                self.halted = true;
+            }
+            return false;
+        }
+        true
+    }
+
+    pub fn enable_miss_classification(&mut self, top_num: usize) {
+        self.miss_classifier = Some(MissClassifier::new(
+            self.cache.get_line_size(),
+            self.cache.get_total_line_num(),
+        ));
+        self.miss_stats_top_num = top_num;
+    }
+
+    pub fn set_memory_tracer(&mut self, memory_tracer: MemoryTracer) {
+        self.memory_tracer = Some(memory_tracer);
+    }
+
+    pub fn set_stack_distance_analyzer(
+        &mut self,
+        stack_distance_analyzer: StackDistanceAnalyzer,
+        output_file_path: &str,
+    ) {
+        self.stack_distance_analyzer =
+            Some((stack_distance_analyzer, output_file_path.to_string()));
+    }
+
+    pub fn set_heatmap(&mut self, heatmap: Heatmap, output_file_path: &str) {
+        self.heatmap = Some((heatmap, output_file_path.to_string()));
+    }
+
+    fn record_data_access(&mut self, addr: Address, is_write: bool, hit: bool) {
+        let (pc, inst) = self.get_accessing_instruction();
+        let inst_count = inst
+            .as_ref()
+            .and_then(get_instruction_count)
+            .unwrap_or(self.instruction_count);
+        // a system call does not load into its destination register
+        let rd = match inst {
+            Some(inst) if !self.system_call_in_progress => get_destination_register(inst),
+            _ => None,
+        };
+
+        if let Some(miss_classifier) = &mut self.miss_classifier {
+            miss_classifier.record(pc, addr, hit);
+        }
+        if let Some(memory_tracer) = &mut self.memory_tracer {
+            memory_tracer.record(addr, is_write, pc, inst_count, hit);
+        }
+        if let Some((stack_distance_analyzer, _)) = &mut self.stack_distance_analyzer {
+            stack_distance_analyzer.record(addr);
+        }
+        if let Some((heatmap, _)) = &mut self.heatmap {
+            heatmap.record(addr, is_write, hit, inst_count);
+        }
+        if let Some(uninit_checker) = &mut self.uninit_checker {
+            if is_write {
+                uninit_checker.mark_word(addr);
+            } else {
+                let inst = if self.system_call_in_progress {
+                    &self.instruction_in_exec_stage
+                } else {
+                    &self.instruction_in_memory_stage
+                };
+                uninit_checker.check_load(addr, pc, || match inst {
+                    Some(inst) => format!("{:?}", inst),
+                    None => "-".to_string(),
+                });
+            }
+        }
+        if let Some(mshr_timing_model) = &mut self.mshr_timing_model {
+            let line_addr = addr & !((1 << self.cache.get_offset_bit_num()) - 1);
+            if is_write {
+                mshr_timing_model.on_store(self.cycle_count, line_addr, hit);
+            } else {
+                mshr_timing_model.on_load(self.cycle_count, line_addr, hit, rd);
+            }
+        }
+    }
+
+    pub fn set_mshr_timing_model(
+        &mut self,
+        mshr_num: usize,
+        miss_penalty: Cycle,
+    ) -> Result<(), String> {
+        self.mshr_timing_model = Some(MshrTimingModel::new(mshr_num, miss_penalty)?);
+        Ok(())
+    }
+
+    pub fn enable_uninit_check(&mut self) {
+        self.uninit_checker = Some(UninitChecker::new());
+    }
+
+    #[allow(dead_code)]
+    pub fn get_uninit_checker(&self) -> Option<&UninitChecker> {
+        self.uninit_checker.as_ref()
+    }
+
+    /// Enables the C extension. The pc advances by 2 past compressed instructions, and
+    /// 32-bit instructions may start at any even address.
+    pub fn enable_compressed_instructions(&mut self) {
+        self.instruction_set.enable_compressed();
+    }
+
+    pub fn enable_code_size_stats(&mut self) {
+        self.code_size_stats = Some(CodeSizeStats::new());
+    }
+
+    /// Checks the source registers of the instruction entering the exec stage.
+    /// A register with a forwarding source is being written by an older instruction.
+    fn check_uninitialized_operands(&mut self) {
+        if let (Some(uninit_checker), Some(inst)) =
+            (&mut self.uninit_checker, &self.instruction_in_exec_stage)
+        {
+            let pc = self.exec_stage_pc.unwrap_or(0);
+            for rs in get_used_source_registers(inst) {
+                let forwarded = match rs {
+                    RegisterId::Int(rs) => self.forwarding_int_sources[rs as usize].is_some(),
+                    RegisterId::Float(rs) => self.forwarding_float_sources[rs as usize].is_some(),
+                };
+                if !forwarded {
+                    uninit_checker.check_register(&rs, pc, || format!("{:?}", inst));
+                }
+            }
+        }
+    }
+
+    fn wait_for_operands(&mut self) {
+        if let (Some(mshr_timing_model), Some(inst)) =
+            (&mut self.mshr_timing_model, &self.instruction_in_exec_stage)
+        {
+            mshr_timing_model.on_exec(
+                self.cycle_count,
+                &get_source_registers(inst),
+                get_destination_register(inst),
+            );
+        }
+    }
+
+    /// Sets the latencies of the multiplier and the divider. They are not pipelined,
+    /// so the whole pipeline stalls for `latency - 1` cycles after each of them.
+    pub fn set_mul_div_latency(&mut self, mul_latency: Cycle, div_latency: Cycle) {
+        self.mul_latency = mul_latency;
+        self.div_latency = div_latency;
+    }
+
+    pub fn get_instruction_set(&self) -> &InstructionSet {
+        &self.instruction_set
+    }
+
+    /// Replaces the instruction set with the one of `isa`, dropping registered instructions.
+    pub fn set_isa(&mut self, isa: Isa) {
+        self.instruction_set = InstructionSet::new(isa);
+    }
+
+    pub fn register_instruction(&mut self, def: InstructionDef) -> Result<(), String> {
+        self.instruction_set.register(def)
+    }
+
+    pub fn get_fma_mode(&self) -> FmaMode {
+        self.fma_mode
+    }
+
+    pub fn set_fma_mode(&mut self, fma_mode: FmaMode) {
+        self.fma_mode = fma_mode;
+    }
+
+    fn wait_for_mul_div(&mut self) {
+        let latency = match &self.instruction_in_exec_stage {
+            Some(inst) if is_multiply_instruction(inst) => self.mul_latency,
+            Some(inst) if is_divide_instruction(inst) => self.div_latency,
+            _ => return,
+        };
+        self.cycle_count += latency - 1;
+        self.mul_div_stall_cycles += latency - 1;
+    }
+
+    pub fn set_prefetcher(&mut self, kind: PrefetcherKind, degree: usize) {
+        self.prefetcher = Some(Prefetcher::new(
+            kind,
+            degree,
+            self.cache.get_line_size(),
+            MEMORY_SIZE,
+        ));
+    }
+
+    fn run_prefetcher(&mut self, addr: Address, hit: bool) {
+        let (pc, _) = self.get_accessing_instruction();
+        let line_addrs = match &mut self.prefetcher {
+            Some(prefetcher) => prefetcher.on_demand_access(pc, addr, hit),
+            None => return,
+        };
+        for line_addr in line_addrs {
+            if self.cache.contains_line(line_addr) {
+                continue;
+            }
+            let victim_line_addr = self.cache.get_victim_line_addr(line_addr);
+            self.process_cache_miss(line_addr);
+            if let Some(prefetcher) = &mut self.prefetcher {
+                prefetcher.on_prefetch_fill(line_addr, victim_line_addr);
+            }
+        }
+    }
+
     fn process_cache_miss(&mut self, addr: Address) {
         let line_addr = addr & !((1 << self.cache.get_offset_bit_num()) - 1);
         let line = self.memory.get_cache_line(line_addr);
@@ -236,104 +772,6 @@ impl Core {
         }
     }
 
-    // #[allow(dead_code)]
-    // pub fn load_byte(&mut self, addr: Address) -> Byte {
-    //     self.increment_memory_access_count();
-    //     let cache_access = self.cache.get_ubyte(addr);
-    //     match cache_access {
-    //         CacheAccess::HitUByte(value) => {
-    //             self.increment_cache_hit_count();
-    //             u8_to_i8(value) as Byte
-    //         }
-    //         CacheAccess::Miss => {
-    //             let value = self.memory.load_byte(addr);
-    //             self.process_cache_miss(addr);
-    //             value
-    //         }
-    //         _ => {
-    //             panic!("invalid cache access");
-    //         }
-    //     }
-    // }
-
-    // #[allow(dead_code)]
-    // pub fn load_ubyte(&mut self, addr: Address) -> UByte {
-    //     self.increment_memory_access_count();
-    //     let cache_access = self.cache.get_ubyte(addr);
-    //     match cache_access {
-    //         CacheAccess::HitUByte(value) => {
-    //             self.increment_cache_hit_count();
-    //             value
-    //         }
-    //         CacheAccess::Miss => {
-    //             let value = self.memory.load_ubyte(addr);
-    //             self.process_cache_miss(addr);
-    //             value
-    //         }
-    //         _ => {
-    //             panic!("invalid cache access");
-    //         }
-    //     }
-    // }
-
-    // #[allow(dead_code)]
-    // pub fn store_byte(&mut self, addr: Address, value: Byte) {
-    //     self.increment_memory_access_count();
-    //     let cache_access = self.cache.set_ubyte(addr, i8_to_u8(value));
-    //     match cache_access {
-    //         CacheAccess::HitSet => {
-    //             self.increment_cache_hit_count();
-    //         }
-    //         CacheAccess::Miss => {
-    //             self.memory.store_byte(addr, value);
-    //             self.process_cache_miss(addr);
-    //         }
-    //         _ => {
-    //             panic!("invalid cache access");
-    //         }
-    //     }
-    // }
-
-    // #[allow(dead_code)]
-    // pub fn load_half(&mut self, addr: Address) -> Half {
-    //     self.increment_memory_access_count();
-    //     let cache_access = self.cache.get_uhalf(addr);
-    //     match cache_access {
-    //         CacheAccess::HitUHalf(value) => {
-    //             self.increment_cache_hit_count();
-    //             u16_to_i16(value)
-    //         }
-    //         CacheAccess::Miss => {
-    //             let value = self.memory.load_half(addr);
-    //             self.process_cache_miss(addr);
-    //             value
-    //         }
-    //         _ => {
-    //             panic!("invalid cache access");
-    //         }
-    //     }
-    // }
-
-    // #[allow(dead_code)]
-    // pub fn load_uhalf(&mut self, addr: Address) -> UHalf {
-    //     self.increment_memory_access_count();
-    //     let cache_access = self.cache.get_uhalf(addr);
-    //     match cache_access {
-    //         CacheAccess::HitUHalf(value) => {
-    //             self.increment_cache_hit_count();
-    //             value
-    //         }
-    //         CacheAccess::Miss => {
-    //             let value = self.memory.load_uhalf(addr);
-    //             self.process_cache_miss(addr);
-    //             value
-    //         }
-    //         _ => {
-    //             panic!("invalid cache access");
-    //         }
-    //     }
-    // }
-
     pub fn read_int(&mut self) -> Word {
         let value = self.sld_vec[self.sld_counter].parse::<i32>().unwrap();
         self.sld_counter += 1;
@@ -347,22 +785,182 @@ impl Core {
         u32_to_i32(fp.get_32_bits())
     }
 
+    pub fn has_input(&self) -> bool {
+        self.sld_counter < self.sld_vec.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn set_input(&mut self, sld_vec: Vec<String>) {
+        self.sld_vec = sld_vec;
+        self.sld_counter = 0;
+    }
+
+    pub fn set_bus(&mut self, bus: Bus) {
+        self.bus = Some(bus);
+    }
+
+    fn load_from_bus(&mut self, addr: Address, size: Address, is_fp: bool) -> Option<Word> {
+        let mut bus = self.bus.take()?;
+        let value = if is_fp {
+            bus.load_word_fp(self, addr)
+        } else {
+            bus.load(self, addr, size)
+        };
+        self.bus = Some(bus);
+        value
+    }
+
+    fn store_to_bus(&mut self, addr: Address, value: Word, size: Address) -> bool {
+        let mut bus = match self.bus.take() {
+            Some(bus) => bus,
+            None => return false,
+        };
+        let claimed = bus.store(self, addr, value, size);
+        self.bus = Some(bus);
+        claimed
+    }
+
+    pub fn load_byte(&mut self, addr: Address) -> Byte {
+        u8_to_i8(self.load_ubyte(addr))
+    }
+
+    pub fn load_ubyte(&mut self, addr: Address) -> UByte {
+        if let Some(value) = self.load_from_bus(addr, 1, false) {
+            return value as UByte;
+        }
+        if !self.check_memory_access(addr, 1, false) {
+            return 0;
+        }
+        self.increment_memory_access_count();
+        let cache_access = self.cache.get_ubyte(addr);
+        match cache_access {
+            CacheAccess::HitUByte(value) => {
+                self.increment_cache_hit_count();
+                self.record_data_access(addr, false, true);
+                self.run_prefetcher(addr, true);
+                value
+            }
+            CacheAccess::Miss => {
+                self.record_data_access(addr, false, false);
+                let value = self.memory.load_ubyte(addr);
+                self.process_cache_miss(addr);
+                self.run_prefetcher(addr, false);
+                value
+            }
+            _ => {
+                panic!("invalid cache access");
+            }
+        }
+    }
+
+    pub fn store_byte(&mut self, addr: Address, value: Byte) {
+        if self.store_to_bus(addr, i8_to_u8(value) as Word, 1) {
+            return;
+        }
+        if !self.check_memory_access(addr, 1, true) {
+            return;
+        }
+        self.increment_memory_access_count();
+        let cache_access = self.cache.set_ubyte(addr, i8_to_u8(value));
+        match cache_access {
+            CacheAccess::HitSet => {
+                self.increment_cache_hit_count();
+                self.record_data_access(addr, true, true);
+                self.run_prefetcher(addr, true);
+            }
+            CacheAccess::Miss => {
+                self.record_data_access(addr, true, false);
+                self.memory.store_byte(addr, value);
+                self.process_cache_miss(addr);
+                self.run_prefetcher(addr, false);
+            }
+            _ => {
+                panic!("invalid cache access");
+            }
+        }
+    }
+
+    pub fn load_half(&mut self, addr: Address) -> Half {
+        u16_to_i16(self.load_uhalf(addr))
+    }
+
+    pub fn load_uhalf(&mut self, addr: Address) -> UHalf {
+        if let Some(value) = self.load_from_bus(addr, 2, false) {
+            return value as UHalf;
+        }
+        if !self.check_memory_access(addr, 2, false) {
+            return 0;
+        }
+        self.increment_memory_access_count();
+        let cache_access = self.cache.get_uhalf(addr);
+        match cache_access {
+            CacheAccess::HitUHalf(value) => {
+                self.increment_cache_hit_count();
+                self.record_data_access(addr, false, true);
+                self.run_prefetcher(addr, true);
+                value
+            }
+            CacheAccess::Miss => {
+                self.record_data_access(addr, false, false);
+                let value = self.memory.load_uhalf(addr);
+                self.process_cache_miss(addr);
+                self.run_prefetcher(addr, false);
+                value
+            }
+            _ => {
+                panic!("invalid cache access");
+            }
+        }
+    }
+
+    pub fn store_half(&mut self, addr: Address, value: Half) {
+        if self.store_to_bus(addr, i16_to_u16(value) as Word, 2) {
+            return;
+        }
+        if !self.check_memory_access(addr, 2, true) {
+            return;
+        }
+        self.increment_memory_access_count();
+        let cache_access = self.cache.set_uhalf(addr, i16_to_u16(value));
+        match cache_access {
+            CacheAccess::HitSet => {
+                self.increment_cache_hit_count();
+                self.record_data_access(addr, true, true);
+                self.run_prefetcher(addr, true);
+            }
+            CacheAccess::Miss => {
+                self.record_data_access(addr, true, false);
+                self.memory.store_half(addr, value);
+                self.process_cache_miss(addr);
+                self.run_prefetcher(addr, false);
+            }
+            _ => {
+                panic!("invalid cache access");
+            }
+        }
+    }
+
     pub fn load_word(&mut self, addr: Address) -> Word {
-        // if addr == IO_ADDRESS {
-        //     let value = self.sld_vec[self.sld_counter].parse::<i32>().unwrap();
-        //     self.sld_counter += 1;
-        //     return value;
-        // }
+        if let Some(value) = self.load_from_bus(addr, 4, false) {
+            return value;
+        }
+        if !self.check_memory_access(addr, 4, false) {
+            return 0;
+        }
         self.increment_memory_access_count();
         let cache_access = self.cache.get_word(addr);
         match cache_access {
             CacheAccess::HitWord(value) => {
                 self.increment_cache_hit_count();
+                self.record_data_access(addr, false, true);
+                self.run_prefetcher(addr, true);
                 value
             }
             CacheAccess::Miss => {
+                self.record_data_access(addr, false, false);
                 let value = self.memory.load_word(addr);
                 self.process_cache_miss(addr);
+                self.run_prefetcher(addr, false);
                 value
             }
             _ => {
@@ -371,53 +969,185 @@ impl Core {
         }
     }
 
-    // pub fn load_word_fp(&mut self, addr: Address) -> Word {
-    //     if addr == IO_ADDRESS {
-    //         let value = self.sld_vec[self.sld_counter].parse::<f32>().unwrap();
-    //         let fp = FloatingPoint::new_f32(value);
-    //         self.sld_counter += 1;
-    //         u32_to_i32(fp.get_32_bits())
-    //     } else {
-    //         self.load_word(addr)
-    //     }
-    // }
+    pub fn load_word_fp(&mut self, addr: Address) -> Word {
+        if let Some(value) = self.load_from_bus(addr, 4, true) {
+            return value;
+        }
+        self.load_word(addr)
+    }
 
-    // #[allow(dead_code)]
-    // pub fn store_half(&mut self, addr: Address, value: Half) {
-    //     self.increment_memory_access_count();
-    //     let cache_access = self.cache.set_uhalf(addr, i16_to_u16(value));
-    //     match cache_access {
-    //         CacheAccess::HitSet => {
-    //             self.increment_cache_hit_count();
-    //         }
-    //         CacheAccess::Miss => {
-    //             self.memory.store_half(addr, value);
-    //             self.process_cache_miss(addr);
-    //         }
-    //         _ => {
-    //             panic!("invalid cache access");
-    //         }
-    //     }
-    // }
+    /// Reads a word through the cache without counting an access, so dirty lines are reflected.
+    pub fn peek_word(&self, addr: Address) -> Word {
+        match self.cache.peek_word(addr) {
+            Some(value) => value,
+            None => self.memory.load_word(addr),
+        }
+    }
+
+    pub fn set_memory_dumper(&mut self, memory_dumper: MemoryDumper) {
+        self.memory_dumper = Some(memory_dumper);
+    }
+
+    fn dump_memory(&mut self) {
+        if let Some(mut memory_dumper) = self.memory_dumper.take() {
+            if let Err(e) = memory_dumper.dump(|addr| self.peek_word(addr)) {
+                eprintln!("Failed in writing memory dump ({}).", e);
+            }
+            self.memory_dumper = Some(memory_dumper);
+        }
+    }
+
+    /// Writes a data image to memory from `base`, keeping the cache coherent.
+    pub fn load_data(&mut self, base: Address, words: &[MemoryValue]) -> Result<(), String> {
+        if !base.is_multiple_of(4) {
+            return Err(format!("base address {:#x} is not word aligned", base));
+        }
+        let end = base as u64 + words.len() as u64 * 4;
+        if end > MEMORY_SIZE {
+            return Err(format!(
+                "data [{:#x}, {:#x}) exceeds the address space",
+                base, end
+            ));
+        }
+        self.extend_program_break(end as Address);
+        self.add_mapped_range(base as u64, end);
+        for (i, &word) in words.iter().enumerate() {
+            let addr = base + i as Address * 4;
+            self.memory.store_word(addr, u32_to_i32(word));
+            self.cache.poke_word(addr, u32_to_i32(word));
+            if let Some(uninit_checker) = &mut self.uninit_checker {
+                uninit_checker.mark_word(addr);
+            }
+        }
+        Ok(())
+    }
+
+    /// Keeps the heap of the brk system call above the loaded program and data.
+    fn extend_program_break(&mut self, end: Address) {
+        self.heap_start = self.heap_start.max(end);
+        self.program_break = self.program_break.max(end);
+    }
+
+    pub fn get_heap_start(&self) -> Address {
+        self.heap_start
+    }
+
+    pub fn get_program_break(&self) -> Address {
+        self.program_break
+    }
+
+    /// Moves the program break, mapping the heap up to it for the memory check.
+    pub fn set_program_break(&mut self, program_break: Address) {
+        self.program_break = program_break;
+        if program_break > self.heap_start {
+            self.add_mapped_range(self.heap_start as u64, program_break as u64);
+        }
+    }
+
+    pub fn get_clock_frequency(&self) -> u64 {
+        self.clock_frequency
+    }
+
+    /// Sets the clock frequency in Hz that the time system calls convert cycles with.
+    pub fn set_clock_frequency(&mut self, clock_frequency: u64) {
+        self.clock_frequency = clock_frequency;
+    }
+
+    /// Reports an illegal instruction in the exec stage and stops the program.
+    fn illegal_instruction(&mut self, reason: &str) {
+        let pc = self.exec_stage_pc.unwrap_or(0);
+        let mut inst_count = self.instruction_count;
+        let inst_string = match &self.instruction_in_exec_stage {
+            Some(inst) => {
+                inst_count = get_instruction_count(inst).unwrap_or(inst_count);
+                format!("{:?}", inst)
+            }
+            None => "?".to_string(),
+        };
+        eprintln!("\nerror: illegal instruction: {}", reason);
+        eprintln!(
+            "    pc: {} ({}), inst_count: {}",
+            pc, inst_string, inst_count
+        );
+        self.halt();
+    }
+
+    /// Reads a CSR for the Zicsr instructions. `time` counts cycles, as if the timer ran
+    /// at the core clock. A CSR that does not exist is an illegal instruction.
+    pub fn read_csr(&mut self, csr: CsrNumber) -> Option<Int> {
+        let counter = match csr {
+            CYCLE | TIME => self.cycle_count,
+            CYCLEH | TIMEH => self.cycle_count >> 32,
+            INSTRET => self.instruction_count,
+            INSTRETH => self.instruction_count >> 32,
+            _ => {
+                let value = self.csr_file.read(csr);
+                if value.is_none() {
+                    self.illegal_instruction(&format!("unknown CSR {:#x}", csr));
+                }
+                return value;
+            }
+        };
+        Some(u32_to_i32(counter as u32))
+    }
+
+    pub fn raise_fp_flags(&mut self, flags: FpFlags) {
+        self.csr_file.raise_fp_flags(flags);
+    }
+
+    /// Resolves the rm field of a float instruction, replacing 0b111 (dynamic) with frm.
+    /// A reserved rounding mode, in the field or in frm, is an illegal instruction.
+    pub fn get_rounding_mode(&mut self, rm: u8) -> Option<RoundingMode> {
+        let bits = if rm == 0b111 {
+            self.csr_file.get_rounding_mode()
+        } else {
+            rm
+        };
+        let mode = RoundingMode::from_bits(bits);
+        if mode.is_none() {
+            self.illegal_instruction(&format!("reserved rounding mode {:#05b}", bits));
+        }
+        mode
+    }
+
+    /// Writes a CSR. A read-only CSR or one that does not exist is an illegal instruction.
+    pub fn write_csr(&mut self, csr: CsrNumber, value: Int) {
+        if is_read_only(csr) {
+            self.illegal_instruction(&format!("write to read-only CSR {:#x}", csr));
+        } else if !self.csr_file.write(csr, value) {
+            self.illegal_instruction(&format!("unknown CSR {:#x}", csr));
+        }
+    }
 
     pub fn print_char(&mut self, value: Word) {
         self.output.push(value as u8);
     }
 
+    #[allow(dead_code)]
+    pub fn get_output(&self) -> &[u8] {
+        &self.output
+    }
+
     pub fn store_word(&mut self, addr: Address, value: Word) {
-        // if addr == IO_ADDRESS {
-        //     self.output.push(value as u8);
-        //     return;
-        // }
+        if self.store_to_bus(addr, value, 4) {
+            return;
+        }
+        if !self.check_memory_access(addr, 4, true) {
+            return;
+        }
         self.increment_memory_access_count();
         let cache_access = self.cache.set_word(addr, value);
         match cache_access {
             CacheAccess::HitSet => {
                 self.increment_cache_hit_count();
+                self.record_data_access(addr, true, true);
+                self.run_prefetcher(addr, true);
             }
             CacheAccess::Miss => {
+                self.record_data_access(addr, true, false);
                 self.memory.store_word(addr, value);
                 self.process_cache_miss(addr);
+                self.run_prefetcher(addr, false);
             }
             _ => {
                 panic!("invalid cache access");
@@ -429,26 +1159,34 @@ impl Core {
         self.instruction_in_write_back_stage = self.instruction_in_memory_stage.clone();
         self.instruction_in_memory_stage = self.instruction_in_exec_stage.clone();
         self.instruction_in_exec_stage = self.decoded_instruction.clone();
+        self.write_back_stage_pc = self.memory_stage_pc;
+        self.memory_stage_pc = self.exec_stage_pc;
+        self.exec_stage_pc = self.decoded_pc;
         if let Some(fetched_instruction) = self.fetched_instruction {
-            let decoded = decode_instruction(fetched_instruction);
-            if let Instruction::Other = decoded {
+            let Some(decoded_inst_struct) = self.decode(fetched_instruction) else {
                 self.decoded_instruction = None;
                 self.fetched_instruction = None;
+                self.decoded_pc = None;
+                self.fetched_pc = None;
                 return;
-            } else {
-                let decoded_inst_struct = create_instruction_struct(decoded);
-                self.decoded_instruction = Some(decoded_inst_struct);
-            }
+            };
+            self.decoded_instruction = Some(decoded_inst_struct);
+            self.decoded_pc = self.fetched_pc;
         } else {
             self.decoded_instruction = None;
+            self.decoded_pc = None;
         }
         self.fetched_instruction = None;
+        self.fetched_pc = None;
     }
 
     pub fn move_instructions_to_next_stage_stalling(&mut self) {
         self.instruction_in_write_back_stage = self.instruction_in_memory_stage.clone();
         self.instruction_in_memory_stage = self.instruction_in_exec_stage.clone();
         self.instruction_in_exec_stage = None;
+        self.write_back_stage_pc = self.memory_stage_pc;
+        self.memory_stage_pc = self.exec_stage_pc;
+        self.exec_stage_pc = None;
     }
 
     pub fn get_forwarding_int_source(&self, rs: Rs) -> Option<&(InstructionCount, Int)> {
@@ -475,9 +1213,6 @@ impl Core {
         inst_cnt: InstructionCount,
         value: FloatingPoint,
     ) {
-        if rs == 0 {
-            return;
-        }
         self.forwarding_float_sources[rs as usize] = Some((inst_cnt, value));
     }
 
@@ -536,6 +1271,10 @@ impl Core {
         self.instruction_count
     }
 
+    pub fn get_cycle_count(&self) -> Cycle {
+        self.cycle_count
+    }
+
     #[allow(dead_code)]
     pub fn show_registers(&self) {
         for i in 0..INT_REGISTER_SIZE {
@@ -637,7 +1376,7 @@ impl Core {
     #[allow(dead_code)]
     fn update_pc_stats(&mut self) {
         if let Some(inst) = self.fetched_instruction {
-            let decoded = decode_instruction(inst);
+            let decoded = self.instruction_set.decode_instruction(inst);
             if let Instruction::Other = decoded {
                 return;
             }
@@ -653,7 +1392,7 @@ impl Core {
         println!("---------- pc stats ----------");
         let mut pc_stats = vec![];
         for (pc, (decoded, inst_count)) in &self.pc_stats {
-            let inst = create_instruction_struct(*decoded);
+            let inst = self.instruction_set.create_instruction_struct(*decoded);
             let inst_name = get_name(&inst);
             pc_stats.push((pc, inst_name, inst_count));
         }
@@ -666,11 +1405,14 @@ impl Core {
     }
 
     fn update_inst_stats(&mut self) {
-        if let Some(inst) = self.get_instruction_in_exec_stage() {
+        if let Some(inst) = &self.instruction_in_exec_stage {
             self.inst_stats
                 .entry(get_name(inst))
                 .and_modify(|e| *e += 1)
                 .or_insert(1);
+            if let Some(code_size_stats) = &mut self.code_size_stats {
+                code_size_stats.add(inst);
+            }
         }
     }
 
@@ -768,6 +1510,9 @@ impl Core {
 
     fn show_memory_stats(&self) {
         println!("memory access count: {}", self.memory_access_count);
+        if self.memory_check != MemoryCheck::Ignore {
+            println!("memory fault count: {}", self.memory_fault_count);
+        }
         println!("cache hit count: {}", self.cache_hit_count);
         println!(
             "cache hit rate: {:.5}%",
@@ -779,27 +1524,171 @@ impl Core {
         );
     }
 
-    fn output_pc_file(&self, path: &str) {
-        let mut file = File::create(path).unwrap();
-        let mut pc_count = 0;
-        loop {
-            let inst = self.instruction_memory.load(pc_count as Address);
-            let decoded = decode_instruction(inst);
-            match decoded {
-                Instruction::Other => {
-                    break;
-                }
-                _ => {
-                    let inst = create_instruction_struct(decoded);
-                    let inst_string = format!("{}: {}", pc_count, get_name(&inst));
-                    file.write_all(inst_string.as_bytes()).unwrap();
-                    file.write_all("\n".as_bytes()).unwrap();
-                    pc_count += 4;
+    fn show_prefetch_stats(&self) {
+        let prefetcher = match &self.prefetcher {
+            Some(prefetcher) => prefetcher,
+            None => return,
+        };
+        let stats = prefetcher.get_stats();
+        let ratio = |numerator: usize, denominator: usize| {
+            if denominator == 0 {
+                0.0
+            } else {
+                numerator as f64 / denominator as f64 * 100.0
+            }
+        };
+        println!("---------- prefetch stats ----------");
+        println!("prefetcher: {:?}", prefetcher.get_kind());
+        println!("prefetch issued count: {}", stats.issued_count);
+        println!("useful prefetch count: {}", stats.useful_count);
+        println!(
+            "prefetch accuracy: {:.5}%",
+            ratio(stats.useful_count, stats.issued_count)
+        );
+        println!(
+            "prefetch coverage: {:.5}%",
+            ratio(
+                stats.useful_count,
+                stats.useful_count + stats.demand_miss_count
+            )
+        );
+        println!(
+            "polluting miss count: {} ({:.5}% of misses)",
+            stats.polluting_miss_count,
+            ratio(stats.polluting_miss_count, stats.demand_miss_count)
+        );
+    }
+
+    fn show_mshr_stats(&self) {
+        let mshr_timing_model = match &self.mshr_timing_model {
+            Some(mshr_timing_model) => mshr_timing_model,
+            None => return,
+        };
+        let stats = mshr_timing_model.get_stats();
+        let stall_cycles = mshr_timing_model.get_stall_cycles();
+        let blocking_stall_cycles = mshr_timing_model.get_blocking_stall_cycles();
+        println!("---------- mshr stats ----------");
+        println!(
+            "mshr num: {}, miss penalty: {}",
+            mshr_timing_model.get_mshr_num(),
+            mshr_timing_model.get_miss_penalty()
+        );
+        println!("miss count: {}", stats.miss_count);
+        println!("secondary miss count: {}", stats.secondary_miss_count);
+        println!("access under miss count: {}", stats.access_under_miss_count);
+        println!("dependency stall cycles: {}", stats.dependency_stall_cycles);
+        println!("mshr full stall cycles: {}", stats.mshr_full_stall_cycles);
+        println!(
+            "cycle count (non-blocking): {}",
+            self.cycle_count + stall_cycles
+        );
+        println!(
+            "cycle count (blocking): {}",
+            self.cycle_count + blocking_stall_cycles
+        );
+        println!(
+            "stall cycles avoided: {}",
+            blocking_stall_cycles as i128 - stall_cycles as i128
+        );
+        let occupancy_cycles = mshr_timing_model.get_occupancy_cycles();
+        let total_cycles: Cycle = occupancy_cycles.iter().sum();
+        let mut weighted_cycles = 0;
+        for (occupancy, &cycles) in occupancy_cycles.iter().enumerate() {
+            weighted_cycles += occupancy as Cycle * cycles;
+            println!(
+                "{} busy mshrs: {} cycles ({:.2}%)",
+                occupancy,
+                cycles,
+                if total_cycles == 0 {
+                    0.0
+                } else {
+                    cycles as f64 / total_cycles as f64 * 100.0
                 }
+            );
+        }
+        if total_cycles != 0 {
+            println!(
+                "average mshr occupancy: {:.5}",
+                weighted_cycles as f64 / total_cycles as f64
+            );
+        }
+    }
+
+    fn show_miss_stats(&self) {
+        let miss_classifier = match &self.miss_classifier {
+            Some(miss_classifier) => miss_classifier,
+            None => return,
+        };
+        println!("---------- miss stats ----------");
+        let total = miss_classifier.get_total();
+        let miss_count = total.get_miss_count();
+        let rate = |count: usize| {
+            if miss_count == 0 {
+                0.0
+            } else {
+                count as f64 / miss_count as f64 * 100.0
+            }
+        };
+        println!(
+            "compulsory miss count: {} ({:.2}%)",
+            total.compulsory_miss_count,
+            rate(total.compulsory_miss_count)
+        );
+        println!(
+            "capacity miss count: {} ({:.2}%)",
+            total.capacity_miss_count,
+            rate(total.capacity_miss_count)
+        );
+        println!(
+            "conflict miss count: {} ({:.2}%)",
+            total.conflict_miss_count,
+            rate(total.conflict_miss_count)
+        );
+        print_filled_with_space(&"pc(inst)".to_string(), 25);
+        println!("accesses    misses      compulsory  capacity    conflict");
+        for (pc, stats) in miss_classifier.get_top_missing_pcs(self.miss_stats_top_num) {
+            let decoded = self
+                .instruction_set
+                .decode_instruction(self.peek_instruction(pc));
+            let inst_name = match decoded {
+                Instruction::Other => "?".to_string(),
+                _ => get_name(&self.instruction_set.create_instruction_struct(decoded)),
+            };
+            print_filled_with_space(&format!("{:>08}({})", pc, inst_name), 25);
+            for count in [
+                stats.access_count,
+                stats.get_miss_count(),
+                stats.compulsory_miss_count,
+                stats.capacity_miss_count,
+            ] {
+                print_filled_with_space(&count.to_string(), 12);
             }
+            println!("{}", stats.conflict_miss_count);
         }
     }
 
+    fn output_pc_file(&self, path: &str) {
+        let mut file = File::create(path).unwrap();
+        for (pc, inst) in self.decode_program() {
+            let inst_string = format!("{}: {}", pc, get_name(&inst));
+            file.write_all(inst_string.as_bytes()).unwrap();
+            file.write_all("\n".as_bytes()).unwrap();
+        }
+    }
+
+    fn show_code_size_stats(&self) {
+        let Some(code_size_stats) = &self.code_size_stats else {
+            return;
+        };
+        println!("---------- code size stats ----------");
+        let mut static_code_size_stats = CodeSizeStats::new();
+        for (_, inst) in self.decode_program() {
+            static_code_size_stats.add(&inst);
+        }
+        static_code_size_stats.show("static");
+        code_size_stats.show("dynamic");
+    }
+
     fn show_current_state(&self) {
         eprint!(
             "\r{} {:>08} pc: {:>06} sp: {:>010}",
@@ -834,11 +1723,34 @@ impl Core {
         self.pc = INSTRUCTION_MEMORY_SIZE as Address;
     }
 
+    /// Stops the program at the end of the current cycle, for instructions in the exec stage.
+    /// The older instruction in the memory stage is written back before the program stops.
+    pub fn halt(&mut self) {
+        self.halted = true;
+        self.halted_in_exec_stage = true;
+    }
+
+    /// Stops the program with `code` as the exit status of the simulator.
+    pub fn exit(&mut self, code: Int) {
+        self.exit_code = Some(code);
+        self.halt();
+    }
+
+    pub fn get_exit_code(&self) -> Option<Int> {
+        self.exit_code
+    }
+
+    /// Stops the program with a register dump, for `ebreak` in the exec stage.
+    /// The registers are dumped when the program stops, after the older instructions finish.
+    pub fn breakpoint(&mut self) {
+        self.breakpoint_pc = self.exec_stage_pc;
+        self.halt();
+    }
+
     pub fn run(
         &mut self,
         verbose: u32,
         interval: u64,
-        // data_file_path: &str,
         ppm_file_path: &str,
         sld_file_path: &str,
         pc_file_path: &str,
@@ -846,14 +1758,12 @@ impl Core {
         let start_time = Instant::now();
         let mut will_stall = false;
         let mut stalling;
-        let mut cycle_num: u128 = 0;
 
         let mut ppm_file = File::create(ppm_file_path).unwrap();
         let mut before_output_len = 0;
 
         self.output_pc_file(pc_file_path);
 
-        // self.load_data_file(data_file_path);
         self.load_sld_file(sld_file_path);
 
         self.update_pc_stats();
@@ -876,10 +1786,26 @@ impl Core {
                 let pc_string = format!("pc: {}", self.get_pc());
                 print_filled_with_space(&pc_string, 15);
             }
-            cycle_num += 1;
+            self.cycle_count += 1;
             if interval != 0 {
                 thread::sleep(Duration::from_millis(interval));
             }
+            if self.halted {
+                if self.halted_in_exec_stage {
+                    // the instruction before the halting one has accessed memory but not written back
+                    self.instruction_in_write_back_stage = self.instruction_in_memory_stage.take();
+                    write_back(self);
+                }
+                if let Some(pc) = self.breakpoint_pc {
+                    println!("Breakpoint at pc {}.", pc);
+                    self.show_registers();
+                }
+                match self.exit_code {
+                    Some(code) => println!("Program exited with code {}.", code),
+                    None => println!("Program halted."),
+                }
+                break;
+            }
             if self.get_pc() >= INSTRUCTION_MEMORY_SIZE as Address {
                 self.pc_history.pop();
                 println!("End of program.");
@@ -901,10 +1827,26 @@ impl Core {
             }
 
             write_back(self);
+            if self.halted {
+                // a stack/heap collision stops before younger instructions access memory
+                continue;
+            }
             memory_access(self);
+            if self.halted {
+                // a trapping memory fault stops before younger instructions execute
+                continue;
+            }
 
             if !stalling {
+                if let Some(memory_dumper) = &self.memory_dumper {
+                    if memory_dumper.is_triggered(self.exec_stage_pc, self.instruction_count) {
+                        self.dump_memory();
+                    }
+                }
+                self.check_uninitialized_operands();
+                self.wait_for_operands();
                 exec_instruction(self);
+                self.wait_for_mul_div();
                 self.increment_instruction_count();
                 if !will_stall {
                     register_fetch(self);
@@ -920,7 +1862,7 @@ impl Core {
 
             self.remove_forwarding_source_if_possible();
 
-            if cycle_num % 1000000 == 0 {
+            if self.cycle_count.is_multiple_of(1000000) {
                 self.show_current_state();
             }
             if before_output_len != self.output.len() {
@@ -941,33 +1883,74 @@ impl Core {
             }
         }
 
+        if let Some(memory_tracer) = &mut self.memory_tracer {
+            if let Err(e) = memory_tracer.flush() {
+                eprintln!("Failed in writing memory trace ({}).", e);
+            }
+        }
+        if let Some(mshr_timing_model) = &mut self.mshr_timing_model {
+            mshr_timing_model.finish(self.cycle_count);
+        }
+        if let Some(memory_dumper) = &self.memory_dumper {
+            if !memory_dumper.is_dumped() {
+                self.dump_memory();
+            }
+        }
+
         // if let Ok(report) = guard.report().build() {
         //     let file = File::create("flamegraph_16_2.svg").unwrap();
         //     report.flamegraph(file).unwrap();
         // };
 
         println!(
-            "inst_count: {}\nelapsed time: {:?}\n{:.2} MIPS",
+            "inst_count: {}\ncycle count: {}\nelapsed time: {:?}\n{:.2} MIPS",
             self.instruction_count,
+            self.cycle_count,
             start_time.elapsed(),
             self.instruction_count as f64 / start_time.elapsed().as_micros() as f64
         );
+        if self.mul_div_stall_cycles > 0 {
+            println!("mul/div stall cycles: {}", self.mul_div_stall_cycles);
+        }
         if verbose == 2 {
             self.show_instruction_count_history();
             self.show_pc_history();
             self.show_register_history();
         }
         self.show_memory_stats();
+        self.memory.show_touched_pages();
+        self.show_mshr_stats();
+        self.show_prefetch_stats();
+        self.show_miss_stats();
+        self.show_code_size_stats();
+        if let Some(stack_monitor) = &mut self.stack_monitor {
+            stack_monitor.show();
+        }
+        if let Some(uninit_checker) = &self.uninit_checker {
+            uninit_checker.show();
+        }
+        if let Some((stack_distance_analyzer, output_file_path)) = &self.stack_distance_analyzer {
+            stack_distance_analyzer.show();
+            if let Err(e) = stack_distance_analyzer.output_file(output_file_path) {
+                eprintln!("Failed in writing stack distance file ({}).", e);
+            }
+        }
+        if let Some((heatmap, output_file_path)) = &self.heatmap {
+            if let Err(e) = heatmap.output_files(output_file_path) {
+                eprintln!("Failed in writing heatmap file ({}).", e);
+            }
+        }
         self.show_output_result();
         self.show_inst_stats();
         self.show_pc_stats();
     }
 }
 
-pub fn disassemble(buf: &Vec<u8>, path: &str) {
+pub fn disassemble(buf: &Vec<u8>, path: &str, instruction_set: &InstructionSet) {
     let mut inst_count = 0;
     let mut inst = 0;
     let mut core = Core::new();
+    core.instruction_set = instruction_set.clone();
     for &byte in buf {
         inst += (byte as u32) << ((inst_count % 4) * 8);
         inst_count += 1;
@@ -976,26 +1959,233 @@ pub fn disassemble(buf: &Vec<u8>, path: &str) {
             inst = 0;
         }
     }
+    // a compressed instruction in the lower half of the last word
+    if inst_count % 4 != 0 {
+        core.store_instruction(inst_count - inst_count % 4, inst);
+    }
     let mut file = File::create(path).unwrap();
-    let mut pc_count = 0;
-    loop {
-        let inst = core.instruction_memory.load(pc_count as Address);
-        let decoded = decode_instruction(inst);
-        match decoded {
-            Instruction::Other => {
-                break;
-            }
-            _ => {
-                let inst = create_instruction_struct(decoded);
-                core.set_decoded_instruction(Some(inst));
-                core.increment_pc();
-                register_fetch(&mut core);
-                let inst = core.decoded_instruction.clone().unwrap();
-                let inst_string = format!("{}: {:?}", pc_count, inst);
-                file.write_all(inst_string.as_bytes()).unwrap();
-                file.write_all("\n".as_bytes()).unwrap();
-                pc_count += 4;
+    for (pc, inst) in core.decode_program() {
+        core.set_pc(pc + get_length(&inst));
+        core.set_decoded_instruction(Some(inst));
+        register_fetch(&mut core);
+        let inst = core.decoded_instruction.clone().unwrap();
+        let inst_string = format!("{}: {:?}", pc, inst);
+        file.write_all(inst_string.as_bytes()).unwrap();
+        file.write_all("\n".as_bytes()).unwrap();
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Runs `program` from address 0 until it returns to the initial ra or stops.
+    pub fn run_program(core: &mut Core, program: &[InstructionValue]) {
+        static RUN_COUNT: AtomicUsize = AtomicUsize::new(0);
+        for (i, &inst) in program.iter().enumerate() {
+            core.store_instruction(i as Address * 4, inst);
+        }
+        core.set_int_register(RA, INSTRUCTION_MEMORY_SIZE as Int);
+        let path = std::env::temp_dir().join(format!(
+            "simulator-test-{}-{}",
+            std::process::id(),
+            RUN_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let path = path.to_str().unwrap();
+        let (ppm_file_path, pc_file_path) = (format!("{}.ppm", path), format!("{}.pc", path));
+        core.run(0, 0, &ppm_file_path, "", &pc_file_path);
+        std::fs::remove_file(ppm_file_path).unwrap();
+        std::fs::remove_file(pc_file_path).unwrap();
+    }
+
+    #[test]
+    fn test_breakpoint_is_precise() {
+        let mut core = Core::new();
+        core.set_isa(Isa::Rv32imf);
+        run_program(
+            &mut core,
+            &[
+                0x10000293, // li t0, 0x100
+                0x00700313, // li t1, 7
+                0x00100073, // ebreak
+                0x0062a023, // sw t1, 0(t0)
+                0x00100393, // li t2, 1
+                0x00008067, // ret
+            ],
+        );
+        assert_eq!(core.get_int_register(6), 7);
+        assert_eq!(core.peek_word(0x100), 0);
+        assert_eq!(core.get_int_register(7), 0);
+
+        let mut core = Core::new();
+        core.set_isa(Isa::Rv32imf);
+        run_program(
+            &mut core,
+            &[
+                0x00300513, // li a0, 3
+                0x05d00893, // li a7, 93
+                0x00100073, // ebreak
+                0x00000073, // ecall
+            ],
+        );
+        assert_eq!(core.get_exit_code(), None);
+    }
+
+    #[test]
+    fn test_f0_is_forwarded() {
+        let mut core = Core::new();
+        core.set_isa(Isa::Rv32imf);
+        core.set_float_register(10, FloatingPoint::new_f32(1.5));
+        core.set_float_register(11, FloatingPoint::new_f32(2.5));
+        core.set_float_register(13, FloatingPoint::new_f32(3.0));
+        run_program(
+            &mut core,
+            &[
+                0x00b50053, // fadd.s ft0, fa0, fa1, rne
+                0x10d00653, // fmul.s fa2, ft0, fa3, rne
+                0x00000013, // nop
+                0x00000013, // nop
+                0x00008067, // ret
+            ],
+        );
+        assert_eq!(core.get_float_register(0).get_f32_value(), 4.0);
+        assert_eq!(core.get_float_register(12).get_f32_value(), 12.0);
+    }
+
+    #[test]
+    fn test_decode_program_stops_at_unknown_funct() {
+        for isa in [Isa::Cpuex2, Isa::Rv32imf] {
+            let mut core = Core::new();
+            core.set_isa(isa);
+            for (i, inst) in [
+                0x00100293, // li t0, 1
+                0x42000033, // op 51 with funct7 0b0100001
+                0x00100313, // li t1, 1
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                core.store_instruction(i as Address * 4, inst);
             }
+            let program = core.decode_program();
+            assert_eq!(program.len(), 1);
+            assert_eq!(get_name(&program[0].1), "addi");
+        }
+    }
+
+    #[test]
+    fn test_halt_writes_back_older_instruction() {
+        let mut core = Core::new();
+        core.set_isa(Isa::Rv32imf);
+        run_program(
+            &mut core,
+            &[
+                0x00300513, // li a0, 3
+                0x05d00893, // li a7, 93
+                0x00500293, // li t0, 5
+                0x00000073, // ecall
+                0x00600293, // li t0, 6
+            ],
+        );
+        assert_eq!(core.get_exit_code(), Some(3));
+        assert_eq!(core.get_int_register(5), 5);
+
+        let mut core = Core::new();
+        run_program(
+            &mut core,
+            &[
+                0x00500293, // li t0, 5
+                0xf14022f3, // csrr t0, mhartid
+                0x00600293, // li t0, 6
+            ],
+        );
+        assert_eq!(core.get_int_register(5), 5);
+    }
+
+    #[test]
+    fn test_trace_counts_the_accessing_instruction() {
+        let path = std::env::temp_dir().join(format!(
+            "test_trace_counts_the_accessing_instruction-{}",
+            std::process::id()
+        ));
+        let path = path.to_str().unwrap();
+        let mut core = Core::new();
+        core.set_memory_tracer(MemoryTracer::new(path, TraceFormat::Dinero, None).unwrap());
+        run_program(
+            &mut core,
+            &[
+                0x10000293, // li t0, 0x100
+                0x0002a303, // lw t1, 0(t0)
+                0x00000013, // nop
+                0x0062a223, // sw t1, 4(t0)
+                0x00008067, // ret
+            ],
+        );
+        let trace = std::fs::read_to_string(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(trace, "0 00000100 00000004 3 m\n1 00000104 0000000c 5 h\n");
+    }
+
+    #[test]
+    fn test_memory_trap_is_precise() {
+        let mut core = Core::new();
+        core.set_memory_check(MemoryCheck::Trap);
+        run_program(
+            &mut core,
+            &[
+                0x04100293, // li t0, 65
+                0x90000337, // lui t1, 0x90000
+                0x00000013, // nop
+                0x00000013, // nop
+                0x00032383, // lw t2, 0(t1)
+                0x00500075, // outchar t0
+                0x00008067, // ret
+            ],
+        );
+        assert_eq!(core.get_output(), b"");
+    }
+
+    #[test]
+    fn test_stack_monitor_stop_is_precise() {
+        let mut core = Core::new();
+        core.set_int_register(SP, 0x1000);
+        core.set_stack_monitor(StackMonitorMode::Stop, 3, None)
+            .unwrap();
+        run_program(
+            &mut core,
+            &[
+                0x04100293, // li t0, 65
+                0x10000313, // li t1, 0x100
+                0x000021b7, // lui gp, 2
+                0x00532023, // sw t0, 0(t1)
+                0x00500075, // outchar t0
+                0x00008067, // ret
+            ],
+        );
+        assert_eq!(core.get_int_register(3), 0x2000);
+        assert_eq!(core.peek_word(0x100), 0);
+        assert_eq!(core.get_output(), b"");
+    }
+
+    #[test]
+    fn test_illegal_csr_access() {
+        for illegal_inst in [
+            0xf14022f3, // csrr t0, mhartid
+            0xc0031073, // csrw cycle, t1
+        ] {
+            let mut core = Core::new();
+            core.set_int_register(6, 5);
+            run_program(
+                &mut core,
+                &[
+                    illegal_inst,
+                    0x00100313, // li t1, 1
+                    0x00008067, // ret
+                ],
+            );
+            assert_eq!(core.get_int_register(6), 5);
+            assert_eq!(core.get_int_register(5), 0);
         }
     }
 }
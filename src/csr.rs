@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use crate::fpu_emulator::*;
+use crate::types::*;
+
+pub type CsrNumber = u16;
+
+pub const FFLAGS: CsrNumber = 0x001;
+pub const FRM: CsrNumber = 0x002;
+pub const FCSR: CsrNumber = 0x003;
+
+pub const CYCLE: CsrNumber = 0xc00;
+pub const TIME: CsrNumber = 0xc01;
+pub const INSTRET: CsrNumber = 0xc02;
+pub const CYCLEH: CsrNumber = 0xc80;
+pub const TIMEH: CsrNumber = 0xc81;
+pub const INSTRETH: CsrNumber = 0xc82;
+
+/// Read-write CSRs without side effects, for the guest to keep values in.
+const SCRATCH_CSRS: [CsrNumber; 3] = [
+    0x040, // uscratch
+    0x140, // sscratch
+    0x340, // mscratch
+];
+
+/// How a Zicsr instruction combines the old value of the CSR with its operand.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsrUpdate {
+    Write,
+    Set,
+    Clear,
+}
+
+impl CsrUpdate {
+    pub fn apply(self, old: Int, operand: Int) -> Int {
+        match self {
+            CsrUpdate::Write => operand,
+            CsrUpdate::Set => old | operand,
+            CsrUpdate::Clear => old & !operand,
+        }
+    }
+}
+
+/// CSRs that hold values written by the guest. The counters are not stored here,
+/// since they read the cycle and instruction counts of the core.
+/// fcsr is a view of frm in bits 7-5 and fflags in bits 4-0.
+pub struct CsrFile {
+    values: HashMap<CsrNumber, Int>,
+    fp_flags: FpFlags,
+    rounding_mode: u8,
+}
+
+impl CsrFile {
+    pub fn new() -> Self {
+        CsrFile {
+            values: SCRATCH_CSRS.iter().map(|&csr| (csr, 0)).collect(),
+            fp_flags: 0,
+            rounding_mode: 0,
+        }
+    }
+
+    /// Returns `None` for CSRs that are not in the file.
+    pub fn read(&self, csr: CsrNumber) -> Option<Int> {
+        match csr {
+            FFLAGS => Some(self.fp_flags as Int),
+            FRM => Some(self.rounding_mode as Int),
+            FCSR => Some(((self.rounding_mode as Int) << 5) | self.fp_flags as Int),
+            _ => self.values.get(&csr).copied(),
+        }
+    }
+
+    /// Returns false for CSRs that are not in the file.
+    pub fn write(&mut self, csr: CsrNumber, value: Int) -> bool {
+        match csr {
+            FFLAGS => self.fp_flags = (value & 0x1f) as FpFlags,
+            FRM => self.rounding_mode = (value & 0x7) as u8,
+            FCSR => {
+                self.fp_flags = (value & 0x1f) as FpFlags;
+                self.rounding_mode = ((value >> 5) & 0x7) as u8;
+            }
+            _ => match self.values.get_mut(&csr) {
+                Some(old) => *old = value,
+                None => return false,
+            },
+        }
+        true
+    }
+
+    /// Accumulates exception flags into fflags.
+    pub fn raise_fp_flags(&mut self, flags: FpFlags) {
+        self.fp_flags |= flags;
+    }
+
+    /// The rounding mode field of frm, which can hold reserved values.
+    pub fn get_rounding_mode(&self) -> u8 {
+        self.rounding_mode
+    }
+}
+
+/// The counter CSRs are in the read-only range 0xc00-0xfff.
+pub fn is_read_only(csr: CsrNumber) -> bool {
+    csr >> 10 == 0b11
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csr_file() {
+        let mut csr_file = CsrFile::new();
+        assert_eq!(csr_file.read(0x340), Some(0));
+        assert!(csr_file.write(0x340, CsrUpdate::Set.apply(0b0110, 0b0011)));
+        assert_eq!(csr_file.read(0x340), Some(0b0111));
+        assert_eq!(CsrUpdate::Clear.apply(0b0111, 0b0011), 0b0100);
+        assert_eq!(csr_file.read(0x341), None);
+        assert!(!csr_file.write(0x341, 0));
+        assert!(is_read_only(CYCLE) && is_read_only(INSTRETH) && !is_read_only(0x340));
+
+        csr_file.raise_fp_flags(FLAG_NX);
+        csr_file.raise_fp_flags(FLAG_DZ);
+        assert!(csr_file.write(FRM, 0b001));
+        assert_eq!(csr_file.read(FCSR), Some(0b001_01001));
+        assert!(csr_file.write(FCSR, 0b100_10000));
+        assert_eq!(csr_file.read(FFLAGS), Some(FLAG_NV as Int));
+        assert_eq!(csr_file.get_rounding_mode(), 0b100);
+    }
+}
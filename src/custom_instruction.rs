@@ -0,0 +1,672 @@
+use std::cmp::Ordering;
+use std::fs;
+
+use crate::core::*;
+use crate::fpu_emulator::*;
+use crate::isa::*;
+use crate::types::*;
+use crate::utils::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Type {
+    Int,
+    Float,
+}
+
+#[derive(Clone, Copy)]
+enum Value {
+    Int(Int),
+    Float(FloatingPoint),
+}
+
+impl Value {
+    fn int(self) -> Int {
+        match self {
+            Value::Int(value) => value,
+            Value::Float(_) => unreachable!("expressions are type-checked when parsed"),
+        }
+    }
+
+    fn float(self) -> FloatingPoint {
+        match self {
+            Value::Float(value) => value,
+            Value::Int(_) => unreachable!("expressions are type-checked when parsed"),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    And,
+    Or,
+    Xor,
+    Shl,
+    Shr,
+    Shru,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    LogicalAnd,
+    LogicalOr,
+}
+
+/// Binary operators with their precedence, longest symbols first so that the tokenizer
+/// can take the first match.
+const BINARY_OPERATORS: [(&str, BinaryOp, u8); 19] = [
+    (">>>", BinaryOp::Shru, 8),
+    ("<<", BinaryOp::Shl, 8),
+    (">>", BinaryOp::Shr, 8),
+    ("<=", BinaryOp::Le, 7),
+    (">=", BinaryOp::Ge, 7),
+    ("==", BinaryOp::Eq, 6),
+    ("!=", BinaryOp::Ne, 6),
+    ("&&", BinaryOp::LogicalAnd, 2),
+    ("||", BinaryOp::LogicalOr, 1),
+    ("+", BinaryOp::Add, 9),
+    ("-", BinaryOp::Sub, 9),
+    ("*", BinaryOp::Mul, 10),
+    ("/", BinaryOp::Div, 10),
+    ("%", BinaryOp::Rem, 10),
+    ("&", BinaryOp::And, 5),
+    ("|", BinaryOp::Or, 3),
+    ("^", BinaryOp::Xor, 4),
+    ("<", BinaryOp::Lt, 7),
+    (">", BinaryOp::Gt, 7),
+];
+
+const OTHER_SYMBOLS: [&str; 6] = ["!", "~", "(", ")", "?", ":"];
+
+impl BinaryOp {
+    fn get_result_type(self, symbol: &str, lhs: Type, rhs: Type) -> Result<Type, String> {
+        use BinaryOp::*;
+        if lhs != rhs {
+            return Err(format!(
+                "operands of '{}' have different types ({:?} and {:?})",
+                symbol, lhs, rhs
+            ));
+        }
+        match self {
+            Add | Sub | Mul | Div => Ok(lhs),
+            Eq | Ne | Lt | Le | Gt | Ge => Ok(Type::Int),
+            Rem | And | Or | Xor | Shl | Shr | Shru | LogicalAnd | LogicalOr => {
+                if lhs == Type::Int {
+                    Ok(Type::Int)
+                } else {
+                    Err(format!("'{}' needs integer operands", symbol))
+                }
+            }
+        }
+    }
+
+    fn apply(self, lhs: Value, rhs: Value) -> Value {
+        use BinaryOp::*;
+        let ordering = match (lhs, rhs) {
+            (Value::Int(a), Value::Int(b)) => a.cmp(&b),
+            (Value::Float(a), Value::Float(b)) => a.cmp(&b),
+            _ => unreachable!("expressions are type-checked when parsed"),
+        };
+        match (self, lhs, rhs) {
+            (Eq, _, _) => Value::Int((ordering == Ordering::Equal) as Int),
+            (Ne, _, _) => Value::Int((ordering != Ordering::Equal) as Int),
+            (Lt, _, _) => Value::Int((ordering == Ordering::Less) as Int),
+            (Le, _, _) => Value::Int((ordering != Ordering::Greater) as Int),
+            (Gt, _, _) => Value::Int((ordering == Ordering::Greater) as Int),
+            (Ge, _, _) => Value::Int((ordering != Ordering::Less) as Int),
+            (Add, Value::Float(a), Value::Float(b)) => Value::Float(a + b),
+            (Sub, Value::Float(a), Value::Float(b)) => Value::Float(a - b),
+            (Mul, Value::Float(a), Value::Float(b)) => Value::Float(a * b),
+            (Div, Value::Float(a), Value::Float(b)) => Value::Float(FloatingPoint::new_f32(
+                a.get_f32_value() / b.get_f32_value(),
+            )),
+            (_, Value::Int(a), Value::Int(b)) => Value::Int(match self {
+                Add => a.wrapping_add(b),
+                Sub => a.wrapping_sub(b),
+                Mul => a.wrapping_mul(b),
+                // the same results as div and rem for division by zero and overflow
+                Div => {
+                    if b == 0 {
+                        -1
+                    } else {
+                        a.wrapping_div(b)
+                    }
+                }
+                Rem => {
+                    if b == 0 {
+                        a
+                    } else {
+                        a.wrapping_rem(b)
+                    }
+                }
+                And => a & b,
+                Or => a | b,
+                Xor => a ^ b,
+                Shl => a.wrapping_shl(b as u32),
+                Shr => a.wrapping_shr(b as u32),
+                Shru => u32_to_i32(i32_to_u32(a).wrapping_shr(b as u32)),
+                LogicalAnd => (a != 0 && b != 0) as Int,
+                LogicalOr => (a != 0 || b != 0) as Int,
+                Eq | Ne | Lt | Le | Gt | Ge => unreachable!(),
+            }),
+            _ => unreachable!("expressions are type-checked when parsed"),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Function {
+    Abs,
+    Sqrt,
+    ToInt,
+    ToFloat,
+}
+
+enum Expr {
+    Operand(usize),
+    Constant(Value),
+    Negate(Box<Expr>),
+    Not(Box<Expr>),
+    BitNot(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    Call(Function, Box<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, operands: &[Value]) -> Value {
+        match self {
+            Expr::Operand(i) => operands[*i],
+            Expr::Constant(value) => *value,
+            Expr::Negate(expr) => match expr.eval(operands) {
+                Value::Int(value) => Value::Int(value.wrapping_neg()),
+                Value::Float(value) => Value::Float(-value),
+            },
+            Expr::Not(expr) => Value::Int((expr.eval(operands).int() == 0) as Int),
+            Expr::BitNot(expr) => Value::Int(!expr.eval(operands).int()),
+            Expr::Binary(op, lhs, rhs) => op.apply(lhs.eval(operands), rhs.eval(operands)),
+            Expr::Conditional(condition, then, otherwise) => {
+                if condition.eval(operands).int() != 0 {
+                    then.eval(operands)
+                } else {
+                    otherwise.eval(operands)
+                }
+            }
+            Expr::Call(function, arg) => {
+                let arg = arg.eval(operands);
+                match (function, arg) {
+                    (Function::Abs, Value::Int(value)) => Value::Int(value.wrapping_abs()),
+                    (Function::Abs, Value::Float(value)) => {
+                        Value::Float(fp_sign_injection(value, FloatingPoint::new(0)))
+                    }
+                    (Function::Sqrt, Value::Float(value)) => {
+                        Value::Float(FloatingPoint::new_f32(value.get_f32_value().sqrt()))
+                    }
+                    (Function::ToInt, Value::Float(value)) => Value::Int(fp_to_int(value)),
+                    (Function::ToFloat, Value::Int(value)) => Value::Float(int_to_fp(value)),
+                    _ => unreachable!("expressions are type-checked when parsed"),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Int(Int),
+    Float(f32),
+    Ident(String),
+    Symbol(&'static str),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let symbols: Vec<&'static str> = BINARY_OPERATORS
+        .iter()
+        .map(|(symbol, _, _)| *symbol)
+        .chain(OTHER_SYMBOLS)
+        .collect();
+    let mut tokens = vec![];
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let c = rest.chars().next().unwrap();
+        let len = if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+                .unwrap_or(rest.len());
+            let number = &rest[..len];
+            let token = if let Some(hex) = number.strip_prefix("0x") {
+                u32::from_str_radix(hex, 16).map(|value| Token::Int(u32_to_i32(value)))
+            } else if let Some(bin) = number.strip_prefix("0b") {
+                u32::from_str_radix(bin, 2).map(|value| Token::Int(u32_to_i32(value)))
+            } else if number.contains('.') {
+                let value = number
+                    .parse::<f32>()
+                    .map_err(|e| format!("invalid number '{}' ({})", number, e))?;
+                Ok(Token::Float(value))
+            } else {
+                number
+                    .parse::<u32>()
+                    .map(|value| Token::Int(u32_to_i32(value)))
+            }
+            .map_err(|e| format!("invalid number '{}' ({})", number, e))?;
+            tokens.push(token);
+            len
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Ident(rest[..len].to_string()));
+            len
+        } else {
+            match symbols.iter().find(|symbol| rest.starts_with(**symbol)) {
+                Some(symbol) => {
+                    tokens.push(Token::Symbol(symbol));
+                    symbol.len()
+                }
+                None => return Err(format!("unexpected character '{}'", c)),
+            }
+        };
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    operands: &'a [(&'a str, Type)],
+}
+
+impl Parser<'_> {
+    fn peek_symbol(&self) -> Option<&'static str> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Symbol(symbol)) => Some(symbol),
+            _ => None,
+        }
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        if self.peek_symbol() == Some(symbol) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), String> {
+        if self.eat(symbol) {
+            Ok(())
+        } else {
+            Err(format!("expected '{}'", symbol))
+        }
+    }
+
+    fn parse_conditional(&mut self) -> Result<(Expr, Type), String> {
+        let (condition, condition_type) = self.parse_binary(1)?;
+        if !self.eat("?") {
+            return Ok((condition, condition_type));
+        }
+        if condition_type != Type::Int {
+            return Err("the condition of '?' must be an integer".to_string());
+        }
+        let (then, then_type) = self.parse_conditional()?;
+        self.expect(":")?;
+        let (otherwise, otherwise_type) = self.parse_conditional()?;
+        if then_type != otherwise_type {
+            return Err(format!(
+                "branches of '?' have different types ({:?} and {:?})",
+                then_type, otherwise_type
+            ));
+        }
+        let expr = Expr::Conditional(Box::new(condition), Box::new(then), Box::new(otherwise));
+        Ok((expr, then_type))
+    }
+
+    /// Precedence climbing over `BINARY_OPERATORS`, all of which are left-associative.
+    fn parse_binary(&mut self, min_precedence: u8) -> Result<(Expr, Type), String> {
+        let (mut lhs, mut lhs_type) = self.parse_unary()?;
+        while let Some(&(symbol, op, precedence)) = BINARY_OPERATORS
+            .iter()
+            .find(|(symbol, _, _)| self.peek_symbol() == Some(*symbol))
+        {
+            if precedence < min_precedence {
+                break;
+            }
+            self.pos += 1;
+            let (rhs, rhs_type) = self.parse_binary(precedence + 1)?;
+            lhs_type = op.get_result_type(symbol, lhs_type, rhs_type)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok((lhs, lhs_type))
+    }
+
+    fn parse_unary(&mut self) -> Result<(Expr, Type), String> {
+        if self.eat("-") {
+            let (expr, expr_type) = self.parse_unary()?;
+            return Ok((Expr::Negate(Box::new(expr)), expr_type));
+        }
+        for symbol in ["!", "~"] {
+            if self.eat(symbol) {
+                let (expr, expr_type) = self.parse_unary()?;
+                if expr_type != Type::Int {
+                    return Err(format!("'{}' needs an integer operand", symbol));
+                }
+                let expr = if symbol == "!" {
+                    Expr::Not(Box::new(expr))
+                } else {
+                    Expr::BitNot(Box::new(expr))
+                };
+                return Ok((expr, Type::Int));
+            }
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<(Expr, Type), String> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        match token {
+            Some(Token::Int(value)) => Ok((Expr::Constant(Value::Int(*value)), Type::Int)),
+            Some(Token::Float(value)) => Ok((
+                Expr::Constant(Value::Float(FloatingPoint::new_f32(*value))),
+                Type::Float,
+            )),
+            Some(Token::Ident(name)) if self.peek_symbol() == Some("(") => {
+                let name = name.clone();
+                self.pos += 1;
+                let (arg, arg_type) = self.parse_conditional()?;
+                self.expect(")")?;
+                let (function, result_type) = match (name.as_str(), arg_type) {
+                    ("abs", _) => (Function::Abs, arg_type),
+                    ("sqrt", Type::Float) => (Function::Sqrt, Type::Float),
+                    ("int", Type::Float) => (Function::ToInt, Type::Int),
+                    ("float", Type::Int) => (Function::ToFloat, Type::Float),
+                    ("sqrt" | "int" | "float", _) => {
+                        return Err(format!("{}() does not take {:?}", name, arg_type));
+                    }
+                    _ => return Err(format!("unknown function '{}'", name)),
+                };
+                Ok((Expr::Call(function, Box::new(arg)), result_type))
+            }
+            Some(Token::Ident(name)) => match self
+                .operands
+                .iter()
+                .position(|(operand, _)| operand == name)
+            {
+                Some(i) => Ok((Expr::Operand(i), self.operands[i].1)),
+                None => Err(format!("unknown operand '{}'", name)),
+            },
+            Some(Token::Symbol("(")) => {
+                let expr = self.parse_conditional()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Some(Token::Symbol(symbol)) => Err(format!("unexpected '{}'", symbol)),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+}
+
+/// Parses `text` with the given operand names and checks that it evaluates to `result_type`.
+fn parse_expression(
+    text: &str,
+    operands: &[(&str, Type)],
+    result_type: Type,
+) -> Result<&'static Expr, String> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        pos: 0,
+        operands,
+    };
+    let (expr, expr_type) = parser.parse_conditional()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(format!("unexpected {:?}", parser.tokens[parser.pos]));
+    }
+    if expr_type != result_type {
+        return Err(format!(
+            "the expression is {:?} but must be {:?}",
+            expr_type, result_type
+        ));
+    }
+    Ok(Box::leak(Box::new(expr)))
+}
+
+fn leak<T>(value: T) -> &'static T {
+    Box::leak(Box::new(value))
+}
+
+/// Creates an instruction whose semantics is given by an expression. `kind` decides the
+/// format, the operands the expression can use (`a`, `b` and `c` for the source registers
+/// and `imm` for the immediate) and what the result is used for:
+///
+/// | kind          | format | operands         | result                      |
+/// |---------------|--------|------------------|-----------------------------|
+/// | int_r         | R      | a, b: int        | int written to xd           |
+/// | int_i         | I      | a, imm: int      | int written to xd           |
+/// | branch        | B      | a, b: int        | int, taken if not zero      |
+/// | output        | S      | a: int (xs2)     | int, low byte is output     |
+/// | float_r       | R      | a, b: float      | float written to fd         |
+/// | float_unary   | R      | a: float         | float written to fd         |
+/// | float_r4      | R4     | a, b, c: float   | float written to fd         |
+/// | float_compare | R      | a, b: float      | int written to xd           |
+/// | float_branch  | B      | a, b: float      | int, taken if not zero      |
+/// | float_to_int  | R      | a: float         | int written to xd           |
+/// | int_to_float  | R      | a: int           | float written to fd         |
+///
+/// Float `+`, `-` and `*` use the same emulated units as fadd, fsub and fmul, while `/` and
+/// `sqrt()` are computed exactly instead of with the fdiv and fsqrt tables.
+pub fn create_custom_instruction(
+    name: &str,
+    kind: &str,
+    op: Op,
+    funct3: Option<Funct3>,
+    funct7: Option<Funct7>,
+    expression: &str,
+) -> Result<InstructionDef, String> {
+    use Type::*;
+    let (format, semantics) = match kind {
+        "int_r" => {
+            let expr = parse_expression(expression, &[("a", Int), ("b", Int)], Int)?;
+            let f = move |a, b| expr.eval(&[Value::Int(a), Value::Int(b)]).int();
+            (Format::R, Semantics::IntR(leak(f)))
+        }
+        "int_i" => {
+            let expr = parse_expression(expression, &[("a", Int), ("imm", Int)], Int)?;
+            let f = move |a, imm| expr.eval(&[Value::Int(a), Value::Int(imm)]).int();
+            (Format::I, Semantics::IntI(leak(f)))
+        }
+        "branch" => {
+            let expr = parse_expression(expression, &[("a", Int), ("b", Int)], Int)?;
+            let f = move |a, b| expr.eval(&[Value::Int(a), Value::Int(b)]).int() != 0;
+            (Format::B, Semantics::Branch(leak(f)))
+        }
+        "output" => {
+            let expr = parse_expression(expression, &[("a", Int)], Int)?;
+            let f = move |core: &mut Core, a| core.print_char(expr.eval(&[Value::Int(a)]).int());
+            (Format::S, Semantics::Output(leak(f)))
+        }
+        "float_r" => {
+            let expr = parse_expression(expression, &[("a", Float), ("b", Float)], Float)?;
+            let f =
+                move |_: &mut Core, a, b| expr.eval(&[Value::Float(a), Value::Float(b)]).float();
+            (Format::R, Semantics::FloatR(leak(f)))
+        }
+        "float_unary" => {
+            let expr = parse_expression(expression, &[("a", Float)], Float)?;
+            let f = move |_: &mut Core, a| expr.eval(&[Value::Float(a)]).float();
+            (Format::R, Semantics::FloatUnary(leak(f)))
+        }
+        "float_r4" => {
+            let operands = [("a", Float), ("b", Float), ("c", Float)];
+            let expr = parse_expression(expression, &operands, Float)?;
+            let f = move |_: &mut Core, a, b, c| {
+                expr.eval(&[Value::Float(a), Value::Float(b), Value::Float(c)])
+                    .float()
+            };
+            (Format::R4, Semantics::FloatR4(leak(f)))
+        }
+        "float_compare" => {
+            let expr = parse_expression(expression, &[("a", Float), ("b", Float)], Int)?;
+            let f = move |_: &mut Core, a, b| expr.eval(&[Value::Float(a), Value::Float(b)]).int();
+            (Format::R, Semantics::FloatCompare(leak(f)))
+        }
+        "float_branch" => {
+            let expr = parse_expression(expression, &[("a", Float), ("b", Float)], Int)?;
+            let f = move |a, b| expr.eval(&[Value::Float(a), Value::Float(b)]).int() != 0;
+            (Format::B, Semantics::FloatBranch(leak(f)))
+        }
+        "float_to_int" => {
+            let expr = parse_expression(expression, &[("a", Float)], Int)?;
+            let f = move |a| expr.eval(&[Value::Float(a)]).int();
+            (Format::R, Semantics::FloatToInt(leak(f)))
+        }
+        "int_to_float" => {
+            let expr = parse_expression(expression, &[("a", Int)], Float)?;
+            let f = move |a| expr.eval(&[Value::Int(a)]).float();
+            (Format::R, Semantics::IntToFloat(leak(f)))
+        }
+        _ => return Err(format!("unknown kind '{}'", kind)),
+    };
+    let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+    Ok(InstructionDef::new(
+        name, format, op, funct3, funct7, semantics,
+    ))
+}
+
+fn parse_field(token: &str, max: u8) -> Result<Option<u8>, String> {
+    if token == "*" {
+        return Ok(None);
+    }
+    let value = if let Some(hex) = token.strip_prefix("0x") {
+        u8::from_str_radix(hex, 16)
+    } else if let Some(bin) = token.strip_prefix("0b") {
+        u8::from_str_radix(bin, 2)
+    } else {
+        token.parse()
+    }
+    .map_err(|e| format!("invalid field '{}' ({})", token, e))?;
+    if value > max {
+        return Err(format!("field '{}' is larger than {}", token, max));
+    }
+    Ok(Some(value))
+}
+
+/// Parses lines of `name kind op funct3 funct7 expression`, where `funct3` and `funct7`
+/// can be `*` to match any value; for `float_r4`, `funct7` is `funct2`.
+fn parse_custom_instructions(text: &str) -> Result<Vec<InstructionDef>, String> {
+    let mut defs = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let mut rest = line.split('#').next().unwrap().trim();
+        if rest.is_empty() {
+            continue;
+        }
+        let mut fields = vec![];
+        for _ in 0..5 {
+            let (field, remaining) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            if field.is_empty() {
+                return Err(format!(
+                    "{}: expected name, kind, op, funct3, funct7 and expression",
+                    i + 1
+                ));
+            }
+            fields.push(field);
+            rest = remaining.trim_start();
+        }
+        let funct7_max = if fields[1] == "float_r4" {
+            0b11
+        } else {
+            0b1111111
+        };
+        let def = parse_field(fields[2], 0b1111111)
+            .and_then(|op| op.ok_or("op must be given".to_string()))
+            .and_then(|op| {
+                let funct3 = parse_field(fields[3], 0b111)?;
+                let funct7 = parse_field(fields[4], funct7_max)?;
+                create_custom_instruction(fields[0], fields[1], op, funct3, funct7, rest)
+            })
+            .map_err(|e| format!("{}: {}: {}", i + 1, fields[0], e))?;
+        defs.push(def);
+    }
+    Ok(defs)
+}
+
+pub fn load_custom_instructions(file_path: &str) -> Result<Vec<InstructionDef>, String> {
+    let text = fs::read_to_string(file_path).map_err(|e| format!("{}: {}", file_path, e))?;
+    parse_custom_instructions(&text).map_err(|e| format!("{}:{}", file_path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval_int(expression: &str, a: Int, b: Int) -> Int {
+        let expr =
+            parse_expression(expression, &[("a", Type::Int), ("b", Type::Int)], Type::Int).unwrap();
+        expr.eval(&[Value::Int(a), Value::Int(b)]).int()
+    }
+
+    #[test]
+    fn test_int_expression() {
+        assert_eq!(eval_int("(a << 2) + b", 3, 1), 13);
+        assert_eq!(eval_int("a + b * 2 - 1", 3, 4), 10);
+        assert_eq!(eval_int("a >>> 28", -1, 0), 0xf);
+        assert_eq!(eval_int("a >> 28", -1, 0), -1);
+        assert_eq!(eval_int("a < b ? b : a", 3, -4), 3);
+        assert_eq!(eval_int("a / b", 7, 0), -1);
+        assert_eq!(eval_int("!a || ~b == 0", 1, -1), 1);
+        assert_eq!(eval_int("abs(a - b) & 0xff", 2, 7), 5);
+    }
+
+    #[test]
+    fn test_float_expression() {
+        let operands = [("a", Type::Float), ("b", Type::Float)];
+        let expr = parse_expression("b < a", &operands, Type::Int).unwrap();
+        let values = [2.0, 1.0].map(|value| Value::Float(FloatingPoint::new_f32(value)));
+        assert_eq!(expr.eval(&values).int(), 1);
+        let expr = parse_expression("int(sqrt(a / 0.5) * b)", &operands, Type::Int).unwrap();
+        assert_eq!(expr.eval(&values).int(), 2);
+    }
+
+    #[test]
+    fn test_type_errors() {
+        let operands = [("a", Type::Float), ("b", Type::Int)];
+        for (expression, result_type) in [
+            ("a + b", Type::Float),
+            ("a % a", Type::Float),
+            ("b ? a : b", Type::Float),
+            ("a", Type::Int),
+            ("c", Type::Int),
+            ("b +", Type::Int),
+            ("(b", Type::Int),
+            ("b b", Type::Int),
+        ] {
+            assert!(parse_expression(expression, &operands, result_type).is_err());
+        }
+    }
+
+    #[test]
+    fn test_parse_custom_instructions() {
+        let text = "\
+# name kind op funct3 funct7 expression
+fbgt float_branch 100 0b110 * b < a
+sh2add int_r 0x33 0b100 0b0010000 (a << 2) + b  # Zba
+";
+        let defs = parse_custom_instructions(text).unwrap();
+        assert_eq!(defs.len(), 2);
+        assert_eq!(defs[0].name, "fbgt");
+        assert_eq!(defs[0].format, Format::B);
+        assert_eq!(defs[0].funct7, None);
+        assert_eq!(defs[1].op, 51);
+        assert_eq!(defs[1].funct7, Some(0b0010000));
+        assert!(parse_custom_instructions("bad int_r 51 8 0 a").is_err());
+        assert!(parse_custom_instructions("bad int_r 51 0 0").is_err());
+        assert!(parse_custom_instructions("bad vector 51 0 0 a").is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use std::fs;
+
+use crate::types::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum DataFormat {
+    /// Raw little-endian words, the same layout as the .bin file
+    Bin,
+    /// One 32-bit hexadecimal word per line (an optional 0x prefix, blank lines and # comments are allowed)
+    Hex,
+}
+
+pub fn load_data_file(file_path: &str, format: DataFormat) -> Result<Vec<MemoryValue>, String> {
+    match format {
+        DataFormat::Bin => {
+            let buf = fs::read(file_path).map_err(|e| format!("{}: {}", file_path, e))?;
+            if buf.len() % 4 != 0 {
+                return Err(format!(
+                    "{}: size {} is not a multiple of 4",
+                    file_path,
+                    buf.len()
+                ));
+            }
+            Ok(buf
+                .chunks_exact(4)
+                .map(|bytes| MemoryValue::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect())
+        }
+        DataFormat::Hex => {
+            let text =
+                fs::read_to_string(file_path).map_err(|e| format!("{}: {}", file_path, e))?;
+            parse_hex_words(&text).map_err(|e| format!("{}:{}", file_path, e))
+        }
+    }
+}
+
+fn parse_hex_words(text: &str) -> Result<Vec<MemoryValue>, String> {
+    let mut words = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let token = line.split('#').next().unwrap().trim();
+        if token.is_empty() {
+            continue;
+        }
+        let digits = token
+            .strip_prefix("0x")
+            .or_else(|| token.strip_prefix("0X"))
+            .unwrap_or(token);
+        let word = MemoryValue::from_str_radix(digits, 16)
+            .map_err(|e| format!("{}: invalid word '{}' ({})", i + 1, token, e))?;
+        words.push(word);
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::*;
+
+    #[test]
+    fn test_parse_hex_words() {
+        let text = "# header\n0x00000001\n\n  DEADbeef  # comment\n0Xff\nabc\n";
+        assert_eq!(parse_hex_words(text), Ok(vec![1, 0xdeadbeef, 0xff, 0xabc]));
+        assert_eq!(parse_hex_words(""), Ok(vec![]));
+        assert!(parse_hex_words("0x123456789")
+            .unwrap_err()
+            .starts_with("1: "));
+        assert!(parse_hex_words("1\n0x12g4\n")
+            .unwrap_err()
+            .starts_with("2: "));
+        assert!(parse_hex_words("0x\n").is_err());
+        assert!(parse_hex_words("1 2\n").is_err());
+    }
+
+    #[test]
+    fn test_load_address() {
+        let mut core = Core::new();
+        assert!(core.load_data(0x1002, &[1]).is_err());
+        assert!(core.load_data(0xffff_fffc, &[1, 2]).is_err());
+        core.load_data(0xffff_fffc, &[3]).unwrap();
+        core.load_data(0x1000, &[0x11223344, 0xaabbccdd]).unwrap();
+        assert_eq!(core.peek_word(0x1000), 0x11223344);
+        assert_eq!(core.peek_word(0x1004), 0xaabbccdd_u32 as Word);
+        assert_eq!(core.peek_word(0xffff_fffc), 3);
+        assert_eq!(core.get_heap_start(), 0x1008);
+    }
+}
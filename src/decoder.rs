@@ -1,3 +1,4 @@
+use crate::isa::Format;
 use crate::types::*;
 
 // pub struct IInstruction {
@@ -173,3 +174,17 @@ pub fn decode_instruction(inst: InstructionValue) -> Instruction {
         InstructionType::Other => Instruction::Other,
     }
 }
+
+/// Decodes `inst` with the field layout of `format`, which the instruction set takes
+/// from the instructions defined on the opcode.
+pub fn decode_instruction_in_format(inst: InstructionValue, format: Format) -> Instruction {
+    match format {
+        Format::I => decode_i_instruction(inst),
+        Format::R => decode_r_instruction(inst),
+        Format::S => decode_s_instruction(inst),
+        Format::J => decode_j_instruction(inst),
+        Format::B => decode_b_instruction(inst),
+        Format::U => decode_u_instruction(inst),
+        Format::R4 => decode_r4_instruction(inst),
+    }
+}
@@ -397,7 +397,7 @@ pub fn fp_to_int(this: FloatingPoint) -> Int {
 }
 
 pub fn int_to_fp(x: Int) -> FloatingPoint {
-    if x == std::i32::MIN {
+    if x == i32::MIN {
         return FloatingPoint { value: 0xcf000000 };
     }
     if x == 0 {
@@ -518,6 +518,534 @@ pub fn fp_xor_sign_injection(this: FloatingPoint, other: FloatingPoint) -> Float
     FloatingPoint { value: y }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum FmaMode {
+    /// Add the exact product to the addend and round once, as the adder of fadd rounds
+    Fused,
+    /// Round the product and then the sum, with the same multiplier and adder as fmul and fadd
+    Unfused,
+}
+
+/// Computes `this * other + addend` for the fused multiply-add instructions.
+pub fn fma_fp(
+    this: FloatingPoint,
+    other: FloatingPoint,
+    addend: FloatingPoint,
+    mode: FmaMode,
+) -> FloatingPoint {
+    match mode {
+        FmaMode::Fused => fused_multiply_add(this, other, addend),
+        FmaMode::Unfused => this * other + addend,
+    }
+}
+
+/// A multiplier that passes its whole product to the adder of fadd. Subnormal inputs are
+/// flushed to zero as in fmul, and the sum is rounded once to nearest even, with the
+/// special values and the sign of zero that the adder gives.
+fn fused_multiply_add(a: FloatingPoint, b: FloatingPoint, c: FloatingPoint) -> FloatingPoint {
+    let (sa, ea, _) = a.get_1_8_23_bits();
+    let (sb, eb, _) = b.get_1_8_23_bits();
+    let (sc, ec, _) = c.get_1_8_23_bits();
+    let negative = sa != sb;
+    if ea == 255 || eb == 255 || ec == 255 {
+        // the adder handles the special values once the product is known
+        let product = if a.get_f32_value().is_nan() {
+            a
+        } else if b.get_f32_value().is_nan() {
+            b
+        } else if ea == 255 || eb == 255 {
+            if ea == 0 || eb == 0 {
+                // inf * 0, which is the NaN the adder gives for inf - inf
+                FloatingPoint::new(0xffc00000)
+            } else {
+                signed_infinity(negative)
+            }
+        } else {
+            signed_zero(negative)
+        };
+        return product + c;
+    }
+    let product = if ea == 0 || eb == 0 {
+        (negative, 0, 0)
+    } else {
+        let ((_, a_mantissa, a_exponent), (_, b_mantissa, b_exponent)) = (unpack(a), unpack(b));
+        (negative, a_mantissa * b_mantissa, a_exponent + b_exponent)
+    };
+    let (result, _) = add_finite(product, unpack(c), RoundingMode::Rne);
+    if result.get_32_bits() & 0x7fffffff == 0 {
+        signed_zero(negative && sc == 1)
+    } else {
+        result
+    }
+}
+
+/// Exception flags of the fflags CSR.
+pub type FpFlags = u8;
+
+pub const FLAG_NX: FpFlags = 0b00001;
+pub const FLAG_UF: FpFlags = 0b00010;
+pub const FLAG_OF: FpFlags = 0b00100;
+pub const FLAG_DZ: FpFlags = 0b01000;
+pub const FLAG_NV: FpFlags = 0b10000;
+
+pub const CANONICAL_NAN: u32 = 0x7fc00000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundingMode {
+    Rne,
+    Rtz,
+    Rdn,
+    Rup,
+    Rmm,
+}
+
+impl RoundingMode {
+    /// Decodes the rm field or frm. Returns `None` for the reserved values and for 0b111
+    /// (dynamic), which has to be replaced with frm first.
+    pub fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0b000 => Some(RoundingMode::Rne),
+            0b001 => Some(RoundingMode::Rtz),
+            0b010 => Some(RoundingMode::Rdn),
+            0b011 => Some(RoundingMode::Rup),
+            0b100 => Some(RoundingMode::Rmm),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(self) -> &'static str {
+        match self {
+            RoundingMode::Rne => "rne",
+            RoundingMode::Rtz => "rtz",
+            RoundingMode::Rdn => "rdn",
+            RoundingMode::Rup => "rup",
+            RoundingMode::Rmm => "rmm",
+        }
+    }
+
+    fn round_to_integer(self, value: f64) -> f64 {
+        match self {
+            RoundingMode::Rne => value.round_ties_even(),
+            RoundingMode::Rtz => value.trunc(),
+            RoundingMode::Rdn => value.floor(),
+            RoundingMode::Rup => value.ceil(),
+            RoundingMode::Rmm => value.round(),
+        }
+    }
+}
+
+fn is_signaling_nan(value: FloatingPoint) -> bool {
+    value.get_f32_value().is_nan() && value.get_32_bits() & 0x400000 == 0
+}
+
+/// Converts to a signed (or, with `signed` false, unsigned) 32-bit integer rounded with `rm`.
+/// NaN and out-of-range values saturate and raise NV, as the F extension specifies.
+pub fn fp_to_int_rounded(this: FloatingPoint, rm: RoundingMode, signed: bool) -> (Int, FpFlags) {
+    let (min, max) = if signed {
+        (i32::MIN as f64, i32::MAX as f64)
+    } else {
+        (0.0, u32::MAX as f64)
+    };
+    let value = this.get_f32_value() as f64;
+    let rounded = rm.round_to_integer(value);
+    let (result, flags) = if value.is_nan() {
+        (max, FLAG_NV)
+    } else if rounded < min {
+        (min, FLAG_NV)
+    } else if rounded > max {
+        (max, FLAG_NV)
+    } else if rounded != value {
+        (rounded, FLAG_NX)
+    } else {
+        (rounded, 0)
+    };
+    (result as i64 as u32 as Int, flags)
+}
+
+/// Converts a signed or unsigned 32-bit integer, given as `i64`, rounded with `rm`.
+pub fn int_to_fp_rounded(value: i64, rm: RoundingMode) -> (FloatingPoint, FpFlags) {
+    round_exact(value < 0, value.unsigned_abs() as u128, 0, false, rm)
+}
+
+/// The 10-bit mask of fclass.s: -inf, negative normal, negative subnormal, -0, +0,
+/// positive subnormal, positive normal, +inf, signaling NaN and quiet NaN.
+pub fn fp_class(this: FloatingPoint) -> Int {
+    let value = this.get_f32_value();
+    let negative = value.is_sign_negative();
+    let bit = if value.is_nan() {
+        if is_signaling_nan(this) {
+            8
+        } else {
+            9
+        }
+    } else if value.is_infinite() {
+        if negative {
+            0
+        } else {
+            7
+        }
+    } else if value == 0.0 {
+        if negative {
+            3
+        } else {
+            4
+        }
+    } else if value.is_subnormal() {
+        if negative {
+            2
+        } else {
+            5
+        }
+    } else if negative {
+        1
+    } else {
+        6
+    };
+    1 << bit
+}
+
+/// Splits a finite value into its sign, integer mantissa and exponent, so that its
+/// magnitude is `mantissa * 2^exponent`.
+fn unpack(value: FloatingPoint) -> (bool, u128, i32) {
+    let (s, e, m) = value.get_1_8_23_bits();
+    if e == 0 {
+        (s == 1, m as u128, -149)
+    } else {
+        (s == 1, (m | 0x800000) as u128, e as i32 - 150)
+    }
+}
+
+fn signed_zero(negative: bool) -> FloatingPoint {
+    FloatingPoint::new(if negative { 0x80000000 } else { 0 })
+}
+
+fn signed_infinity(negative: bool) -> FloatingPoint {
+    FloatingPoint::new(if negative { 0xff800000 } else { 0x7f800000 })
+}
+
+/// The canonical NaN, with NV if any of `operands` is a signaling NaN.
+fn nan_result(operands: &[FloatingPoint]) -> (FloatingPoint, FpFlags) {
+    let flags = if operands.iter().any(|&operand| is_signaling_nan(operand)) {
+        FLAG_NV
+    } else {
+        0
+    };
+    (FloatingPoint::new(CANONICAL_NAN), flags)
+}
+
+/// Drops the low `shift` bits of `mantissa` and rounds what is left with `rm`. `sticky`
+/// stands for a nonzero part below the dropped bits. Returns the rounded mantissa and
+/// whether it is inexact.
+fn round_mantissa(
+    mantissa: u128,
+    shift: i32,
+    sticky: bool,
+    negative: bool,
+    rm: RoundingMode,
+) -> (u128, bool) {
+    if shift <= 0 {
+        return (mantissa << -shift, sticky);
+    }
+    // mantissas have at most 121 bits, so a longer shift drops everything just the same
+    let shift = shift.min(126);
+    let (kept, rest, half) = (
+        mantissa >> shift,
+        mantissa & ((1 << shift) - 1),
+        1 << (shift - 1),
+    );
+    let inexact = rest != 0 || sticky;
+    let round_up = match rm {
+        RoundingMode::Rne => rest > half || (rest == half && (sticky || kept & 1 == 1)),
+        RoundingMode::Rtz => false,
+        RoundingMode::Rdn => negative && inexact,
+        RoundingMode::Rup => !negative && inexact,
+        RoundingMode::Rmm => rest >= half,
+    };
+    (kept + round_up as u128, inexact)
+}
+
+/// Rounds the exact result `(-1)^negative * mantissa * 2^exponent` of an operation to
+/// single precision with `rm`, and returns it with the flags it raises. `sticky` means the
+/// exact result is larger in magnitude by less than `2^exponent`; callers that set it keep
+/// at least 26 bits in `mantissa`, so that it stays below the round bit.
+fn round_exact(
+    negative: bool,
+    mantissa: u128,
+    exponent: i32,
+    sticky: bool,
+    rm: RoundingMode,
+) -> (FloatingPoint, FpFlags) {
+    if mantissa == 0 {
+        return (signed_zero(negative), 0);
+    }
+    // the exponents of the leading bit and of the last bit kept, which is the 24th bit
+    // or the last bit of the subnormals
+    let leading = exponent + 127 - mantissa.leading_zeros() as i32;
+    let mut last = (leading - 23).max(-149);
+    let (mut kept, inexact) = round_mantissa(mantissa, last - exponent, sticky, negative, rm);
+    if kept == 1 << 24 {
+        kept >>= 1;
+        last += 1;
+    }
+    let biased_exponent = if kept >> 23 == 0 { 0 } else { last + 150 };
+    if biased_exponent >= 255 {
+        let to_infinity = match rm {
+            RoundingMode::Rne | RoundingMode::Rmm => true,
+            RoundingMode::Rtz => false,
+            RoundingMode::Rdn => negative,
+            RoundingMode::Rup => !negative,
+        };
+        let result = if to_infinity {
+            signed_infinity(negative)
+        } else {
+            FloatingPoint::new(signed_zero(negative).get_32_bits() | 0x7f7fffff)
+        };
+        return (result, FLAG_OF | FLAG_NX);
+    }
+    let mut flags = if inexact { FLAG_NX } else { 0 };
+    // tiny if the result is below the smallest normal when rounded to 24 bits with an
+    // unbounded exponent
+    if inexact && leading < -126 {
+        let (unbounded, _) =
+            round_mantissa(mantissa, leading - 23 - exponent, sticky, negative, rm);
+        if leading < -127 || unbounded >> 24 == 0 {
+            flags |= FLAG_UF;
+        }
+    }
+    let bits = ((biased_exponent as u32) << 23) | (kept as u32 & 0x7fffff);
+    (
+        FloatingPoint::new(signed_zero(negative).get_32_bits() | bits),
+        flags,
+    )
+}
+
+/// Rounds the sum of two finite values `(negative, mantissa, exponent)` with mantissas of
+/// up to 48 bits. The larger one is shifted up to 120 bits, so that when the smaller one
+/// does not fit beside it, its low bits are far enough below the round bit to be sticky.
+fn add_finite(
+    a: (bool, u128, i32),
+    b: (bool, u128, i32),
+    rm: RoundingMode,
+) -> (FloatingPoint, FpFlags) {
+    // an exact zero sum is +0, or -0 when rounding down, unless both operands are -0
+    let zero_sum = (
+        signed_zero(if a.0 == b.0 {
+            a.0
+        } else {
+            rm == RoundingMode::Rdn
+        }),
+        0,
+    );
+    if a.1 == 0 || b.1 == 0 {
+        let (negative, mantissa, exponent) = if a.1 == 0 { b } else { a };
+        if mantissa == 0 {
+            return zero_sum;
+        }
+        return round_exact(negative, mantissa, exponent, false, rm);
+    }
+    let (high, low) = if a.2 >= b.2 { (a, b) } else { (b, a) };
+    let exponent = low
+        .2
+        .max(high.2 - (120 - (128 - high.1.leading_zeros() as i32)));
+    let high_mantissa = high.1 << (high.2 - exponent);
+    let (low_mantissa, lost) = if low.2 >= exponent {
+        (low.1 << (low.2 - exponent), false)
+    } else {
+        let shift = (exponent - low.2).min(64);
+        (low.1 >> shift, low.1 & ((1 << shift) - 1) != 0)
+    };
+    if high.0 == low.0 {
+        return round_exact(high.0, high_mantissa + low_mantissa, exponent, lost, rm);
+    }
+    // the lost bits are subtracted too, so borrow one from the bits that are kept
+    let low_mantissa = low_mantissa + lost as u128;
+    match high_mantissa.cmp(&low_mantissa) {
+        Ordering::Greater => round_exact(high.0, high_mantissa - low_mantissa, exponent, lost, rm),
+        Ordering::Less => round_exact(low.0, low_mantissa - high_mantissa, exponent, lost, rm),
+        Ordering::Equal => zero_sum,
+    }
+}
+
+/// IEEE 754 single-precision operations with exception flags, rounded with `rm`. They
+/// compute the exact result in integers (or the leading bits of it and whether anything
+/// is left) and round it once.
+pub fn ieee_add(a: FloatingPoint, b: FloatingPoint, rm: RoundingMode) -> (FloatingPoint, FpFlags) {
+    let (a_value, b_value) = (a.get_f32_value(), b.get_f32_value());
+    if a_value.is_nan() || b_value.is_nan() {
+        nan_result(&[a, b])
+    } else if a_value.is_infinite() && b_value == -a_value {
+        (FloatingPoint::new(CANONICAL_NAN), FLAG_NV)
+    } else if a_value.is_infinite() {
+        (a, 0)
+    } else if b_value.is_infinite() {
+        (b, 0)
+    } else {
+        add_finite(unpack(a), unpack(b), rm)
+    }
+}
+
+pub fn ieee_mul(a: FloatingPoint, b: FloatingPoint, rm: RoundingMode) -> (FloatingPoint, FpFlags) {
+    let (a_value, b_value) = (a.get_f32_value(), b.get_f32_value());
+    let negative = a_value.is_sign_negative() != b_value.is_sign_negative();
+    if a_value.is_nan() || b_value.is_nan() {
+        nan_result(&[a, b])
+    } else if a_value.is_infinite() || b_value.is_infinite() {
+        if a_value == 0.0 || b_value == 0.0 {
+            (FloatingPoint::new(CANONICAL_NAN), FLAG_NV)
+        } else {
+            (signed_infinity(negative), 0)
+        }
+    } else {
+        let ((_, a_mantissa, a_exponent), (_, b_mantissa, b_exponent)) = (unpack(a), unpack(b));
+        round_exact(
+            negative,
+            a_mantissa * b_mantissa,
+            a_exponent + b_exponent,
+            false,
+            rm,
+        )
+    }
+}
+
+pub fn ieee_div(a: FloatingPoint, b: FloatingPoint, rm: RoundingMode) -> (FloatingPoint, FpFlags) {
+    let (a_value, b_value) = (a.get_f32_value(), b.get_f32_value());
+    let negative = a_value.is_sign_negative() != b_value.is_sign_negative();
+    if a_value.is_nan() || b_value.is_nan() {
+        nan_result(&[a, b])
+    } else if (a_value.is_infinite() && b_value.is_infinite()) || (a_value == 0.0 && b_value == 0.0)
+    {
+        (FloatingPoint::new(CANONICAL_NAN), FLAG_NV)
+    } else if a_value.is_infinite() {
+        (signed_infinity(negative), 0)
+    } else if b_value == 0.0 {
+        (signed_infinity(negative), FLAG_DZ)
+    } else if a_value == 0.0 || b_value.is_infinite() {
+        (signed_zero(negative), 0)
+    } else {
+        let ((_, a_mantissa, a_exponent), (_, b_mantissa, b_exponent)) = (unpack(a), unpack(b));
+        // a dividend of 100 bits leaves a quotient of at least 76, and the remainder is sticky
+        let shift = 100 - (128 - a_mantissa.leading_zeros() as i32);
+        let dividend = a_mantissa << shift;
+        round_exact(
+            negative,
+            dividend / b_mantissa,
+            a_exponent - shift - b_exponent,
+            !dividend.is_multiple_of(b_mantissa),
+            rm,
+        )
+    }
+}
+
+pub fn ieee_sqrt(a: FloatingPoint, rm: RoundingMode) -> (FloatingPoint, FpFlags) {
+    let value = a.get_f32_value();
+    if value.is_nan() {
+        nan_result(&[a])
+    } else if value == 0.0 || value == f32::INFINITY {
+        (a, 0)
+    } else if value < 0.0 {
+        (FloatingPoint::new(CANONICAL_NAN), FLAG_NV)
+    } else {
+        let (_, mantissa, exponent) = unpack(a);
+        // a radicand of at least 100 bits with an even exponent leaves a root of at least
+        // 50 bits, and the remainder is sticky
+        let mut shift = 100 - (128 - mantissa.leading_zeros() as i32);
+        if (exponent - shift) % 2 != 0 {
+            shift += 1;
+        }
+        let radicand = mantissa << shift;
+        let root = radicand.isqrt();
+        round_exact(
+            false,
+            root,
+            (exponent - shift) / 2,
+            root * root != radicand,
+            rm,
+        )
+    }
+}
+
+/// `a * b + c` rounded once, with the exact product of up to 48 bits added to `c`.
+pub fn ieee_fma(
+    a: FloatingPoint,
+    b: FloatingPoint,
+    c: FloatingPoint,
+    rm: RoundingMode,
+) -> (FloatingPoint, FpFlags) {
+    let (a_value, b_value, c_value) = (a.get_f32_value(), b.get_f32_value(), c.get_f32_value());
+    let negative = a_value.is_sign_negative() != b_value.is_sign_negative();
+    // 0 * inf is invalid even when c is a quiet NaN
+    if (a_value == 0.0 && b_value.is_infinite()) || (a_value.is_infinite() && b_value == 0.0) {
+        (FloatingPoint::new(CANONICAL_NAN), FLAG_NV)
+    } else if a_value.is_nan() || b_value.is_nan() || c_value.is_nan() {
+        nan_result(&[a, b, c])
+    } else if a_value.is_infinite() || b_value.is_infinite() {
+        if c_value.is_infinite() && c_value.is_sign_negative() != negative {
+            (FloatingPoint::new(CANONICAL_NAN), FLAG_NV)
+        } else {
+            (signed_infinity(negative), 0)
+        }
+    } else if c_value.is_infinite() {
+        (c, 0)
+    } else {
+        let ((_, a_mantissa, a_exponent), (_, b_mantissa, b_exponent)) = (unpack(a), unpack(b));
+        add_finite(
+            (negative, a_mantissa * b_mantissa, a_exponent + b_exponent),
+            unpack(c),
+            rm,
+        )
+    }
+}
+
+/// feq.s, flt.s and fle.s. NaN compares false, and raises NV if it is signaling, or
+/// for the ordered comparisons (`signaling`) if it is any NaN.
+pub fn ieee_compare(
+    a: FloatingPoint,
+    b: FloatingPoint,
+    signaling: bool,
+    compare: fn(f32, f32) -> bool,
+) -> (Int, FpFlags) {
+    let (a_value, b_value) = (a.get_f32_value(), b.get_f32_value());
+    let flags = if is_signaling_nan(a)
+        || is_signaling_nan(b)
+        || (signaling && (a_value.is_nan() || b_value.is_nan()))
+    {
+        FLAG_NV
+    } else {
+        0
+    };
+    (compare(a_value, b_value) as Int, flags)
+}
+
+/// fmin.s and fmax.s: a NaN operand is ignored, -0 is less than +0, and NaN is
+/// returned only if both operands are NaN.
+pub fn ieee_min_max(a: FloatingPoint, b: FloatingPoint, max: bool) -> (FloatingPoint, FpFlags) {
+    let (a_value, b_value) = (a.get_f32_value(), b.get_f32_value());
+    let flags = if is_signaling_nan(a) || is_signaling_nan(b) {
+        FLAG_NV
+    } else {
+        0
+    };
+    let result = if a_value.is_nan() && b_value.is_nan() {
+        FloatingPoint::new(CANONICAL_NAN)
+    } else if a_value.is_nan() {
+        b
+    } else if b_value.is_nan() {
+        a
+    } else if a_value == b_value {
+        // only differs for zeros of opposite signs
+        if a_value.is_sign_negative() != max {
+            a
+        } else {
+            b
+        }
+    } else if (a_value < b_value) != max {
+        a
+    } else {
+        b
+    };
+    (result, flags)
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::{stdout, Write};
@@ -747,7 +1275,7 @@ mod tests {
                 for m in min_m..=max_m {
                     let op = (s << 31) + (e << 23) + m;
                     let float = f32::from_bits(op) as f64;
-                    if float < std::i32::MIN as f64 || float > std::i32::MAX as f64 {
+                    if float < i32::MIN as f64 || float > i32::MAX as f64 {
                         continue;
                     }
                     let fp = FloatingPoint::new(op);
@@ -763,13 +1291,11 @@ mod tests {
     use float_next_after::NextAfter;
     #[test]
     fn test_int_to_fp() {
-        for x in std::i32::MIN..=std::i32::MAX {
+        for x in i32::MIN..=i32::MAX {
             if x % 1000000 == 0 {
                 print!(
                     "\r{:.0}%",
-                    (x as f32 - std::i32::MIN as f32)
-                        / (std::i32::MAX as f32 - std::i32::MIN as f32 + 1.)
-                        * 100.0
+                    (x as f32 - i32::MIN as f32) / (i32::MAX as f32 - i32::MIN as f32 + 1.) * 100.0
                 );
                 stdout().flush().unwrap();
             }
@@ -793,4 +1319,229 @@ mod tests {
         }
         println!();
     }
+
+    #[test]
+    fn test_rounding_modes() {
+        use RoundingMode::*;
+        let convert =
+            |value: f32, rm, signed| fp_to_int_rounded(FloatingPoint::new_f32(value), rm, signed);
+        for (rm, expected) in [
+            (Rne, [4, -4, 6]),
+            (Rtz, [4, -4, 5]),
+            (Rdn, [4, -5, 5]),
+            (Rup, [5, -4, 6]),
+            (Rmm, [5, -5, 6]),
+        ] {
+            for (value, expected) in [4.5, -4.5, 5.5].into_iter().zip(expected) {
+                assert_eq!(
+                    convert(value, rm, true),
+                    (expected, FLAG_NX),
+                    "{} {:?}",
+                    value,
+                    rm
+                );
+            }
+        }
+        assert_eq!(convert(3.0, Rne, true), (3, 0));
+        assert_eq!(convert(-1.0, Rne, false), (0, FLAG_NV));
+        assert_eq!(convert(-0.25, Rtz, false), (0, FLAG_NX));
+        assert_eq!(convert(4e9, Rne, false), (4_000_000_000_u32 as Int, 0));
+        assert_eq!(convert(4e9, Rne, true), (i32::MAX, FLAG_NV));
+        assert_eq!(convert(f32::NAN, Rne, true), (i32::MAX, FLAG_NV));
+
+        // 2^24 + 1 is halfway between two floats
+        let convert = |value: i64, rm| {
+            let (result, flags) = int_to_fp_rounded(value, rm);
+            (result.get_f32_value(), flags)
+        };
+        assert_eq!(convert(16_777_217, Rne), (16_777_216.0, FLAG_NX));
+        assert_eq!(convert(16_777_217, Rup), (16_777_218.0, FLAG_NX));
+        assert_eq!(convert(16_777_217, Rmm), (16_777_218.0, FLAG_NX));
+        assert_eq!(convert(-16_777_217, Rtz), (-16_777_216.0, FLAG_NX));
+        assert_eq!(convert(-16_777_217, Rdn), (-16_777_218.0, FLAG_NX));
+        assert_eq!(convert(u32::MAX as i64, Rtz), (4_294_967_040.0, FLAG_NX));
+        assert_eq!(convert(-3, Rne), (-3.0, 0));
+    }
+
+    #[test]
+    fn test_ieee_flags() {
+        use RoundingMode::*;
+        let fp = FloatingPoint::new_f32;
+        assert_eq!(ieee_add(fp(1.0), fp(2.0), Rne), (fp(3.0), 0));
+        assert_eq!(ieee_add(fp(1.0), fp(1e-10), Rne), (fp(1.0), FLAG_NX));
+        assert_eq!(
+            ieee_add(fp(f32::MAX), fp(f32::MAX), Rne),
+            (fp(f32::INFINITY), FLAG_OF | FLAG_NX)
+        );
+        let (result, flags) = ieee_add(fp(f32::INFINITY), fp(f32::NEG_INFINITY), Rne);
+        assert_eq!((result.get_32_bits(), flags), (CANONICAL_NAN, FLAG_NV));
+        assert_eq!(
+            ieee_mul(fp(1e-30), fp(1e-30), Rne),
+            (fp(0.0), FLAG_UF | FLAG_NX)
+        );
+        assert_eq!(
+            ieee_div(fp(1.0), fp(0.0), Rne),
+            (fp(f32::INFINITY), FLAG_DZ)
+        );
+        assert_eq!(ieee_div(fp(1.0), fp(3.0), Rne), (fp(1.0 / 3.0), FLAG_NX));
+        assert_eq!(ieee_sqrt(fp(4.0), Rne), (fp(2.0), 0));
+        assert_eq!(ieee_sqrt(fp(-1.0), Rne).1, FLAG_NV);
+        let signaling_nan = FloatingPoint::new(0x7f800001);
+        assert_eq!(
+            ieee_compare(signaling_nan, fp(1.0), false, |a, b| a == b),
+            (0, FLAG_NV)
+        );
+        assert_eq!(
+            ieee_compare(fp(f32::NAN), fp(1.0), false, |a, b| a == b),
+            (0, 0)
+        );
+        assert_eq!(
+            ieee_compare(fp(f32::NAN), fp(1.0), true, |a, b| a < b),
+            (0, FLAG_NV)
+        );
+        assert_eq!(ieee_min_max(fp(f32::NAN), fp(1.0), false), (fp(1.0), 0));
+        assert_eq!(
+            ieee_min_max(fp(-0.0), fp(0.0), false).0.get_32_bits(),
+            0x80000000
+        );
+        assert_eq!(ieee_min_max(fp(-0.0), fp(0.0), true).0.get_32_bits(), 0);
+
+        assert_eq!(fp_class(fp(f32::NEG_INFINITY)), 1 << 0);
+        assert_eq!(fp_class(fp(-0.0)), 1 << 3);
+        assert_eq!(fp_class(fp(1e-40)), 1 << 5);
+        assert_eq!(fp_class(fp(1.0)), 1 << 6);
+        assert_eq!(fp_class(signaling_nan), 1 << 8);
+        assert_eq!(fp_class(fp(f32::NAN)), 1 << 9);
+    }
+
+    #[test]
+    fn test_ieee_rounding() {
+        use RoundingMode::*;
+        let fp = FloatingPoint::new_f32;
+        let bits = |(result, flags): (FloatingPoint, FpFlags)| (result.get_32_bits(), flags);
+        let one_up = 1.0_f32.next_up();
+        let tiny = fp(2.0_f32.powi(-30));
+        assert_eq!(ieee_add(fp(1.0), tiny, Rne), (fp(1.0), FLAG_NX));
+        assert_eq!(ieee_add(fp(1.0), tiny, Rup), (fp(one_up), FLAG_NX));
+        assert_eq!(ieee_add(fp(one_up), -tiny, Rtz), (fp(1.0), FLAG_NX));
+        assert_eq!(ieee_add(fp(-1.0), -tiny, Rdn), (fp(-one_up), FLAG_NX));
+        // an exact zero sum is -0 only when rounding down
+        assert_eq!(bits(ieee_add(fp(1.0), fp(-1.0), Rne)), (0, 0));
+        assert_eq!(bits(ieee_add(fp(1.0), fp(-1.0), Rdn)), (0x80000000, 0));
+        // overflow gives the largest finite value when rounding toward zero
+        assert_eq!(
+            ieee_mul(fp(f32::MAX), fp(2.0), Rtz),
+            (fp(f32::MAX), FLAG_OF | FLAG_NX)
+        );
+        assert_eq!(
+            ieee_mul(fp(-f32::MAX), fp(2.0), Rup),
+            (fp(-f32::MAX), FLAG_OF | FLAG_NX)
+        );
+        assert_eq!(
+            ieee_mul(fp(-f32::MAX), fp(2.0), Rdn),
+            (fp(f32::NEG_INFINITY), FLAG_OF | FLAG_NX)
+        );
+        // underflow rounds to the smallest subnormal, or to zero
+        assert_eq!(
+            bits(ieee_mul(fp(1e-30), fp(1e-30), Rup)),
+            (1, FLAG_UF | FLAG_NX)
+        );
+        assert_eq!(
+            bits(ieee_mul(fp(1e-30), fp(1e-30), Rne)),
+            (0, FLAG_UF | FLAG_NX)
+        );
+        // 1 / 3 rounds up to nearest, and the square root of 2 down
+        let third = 1.0_f32 / 3.0;
+        assert_eq!(ieee_div(fp(1.0), fp(3.0), Rup), (fp(third), FLAG_NX));
+        assert_eq!(
+            ieee_div(fp(1.0), fp(3.0), Rdn),
+            (fp(third.next_down()), FLAG_NX)
+        );
+        assert_eq!(
+            ieee_div(fp(-1.0), fp(3.0), Rtz),
+            (fp(-third.next_down()), FLAG_NX)
+        );
+        let root = 2.0_f32.sqrt();
+        assert_eq!(ieee_sqrt(fp(2.0), Rdn), (fp(root), FLAG_NX));
+        assert_eq!(ieee_sqrt(fp(2.0), Rup), (fp(root.next_up()), FLAG_NX));
+        // (1 + 2^-12)^2 - 1 is exact when the product is not rounded
+        let a = fp(1.0 + 2.0_f32.powi(-12));
+        assert_eq!(
+            ieee_fma(a, a, fp(-1.0), Rne),
+            (fp(2.0_f32.powi(-11) + 2.0_f32.powi(-24)), 0)
+        );
+        assert_eq!(
+            ieee_fma(a, a, fp(-4.0), Rtz),
+            (fp(-3.0 + 2.0_f32.powi(-11) + 2.0_f32.powi(-22)), FLAG_NX)
+        );
+    }
+
+    #[test]
+    fn test_ieee_round_to_nearest_even() {
+        // the operations with rne agree with those of the host, for any operands and for
+        // operands of close exponents, whose sums cancel
+        let mut rng = rand::thread_rng();
+        let mut random = || {
+            let bits: u32 = rng.gen();
+            if rng.gen() {
+                FloatingPoint::new(bits)
+            } else {
+                FloatingPoint::new(bits & 0x807fffff | rng.gen_range(120..136) << 23)
+            }
+        };
+        for _ in 0..100000 {
+            let (a, b, c) = (random(), random(), random());
+            let (x, y, z) = (a.get_f32_value(), b.get_f32_value(), c.get_f32_value());
+            for (result, expected) in [
+                (ieee_add(a, b, RoundingMode::Rne).0, x + y),
+                (ieee_mul(a, b, RoundingMode::Rne).0, x * y),
+                (ieee_div(a, b, RoundingMode::Rne).0, x / y),
+                (ieee_sqrt(a, RoundingMode::Rne).0, x.sqrt()),
+                (ieee_fma(a, b, c, RoundingMode::Rne).0, x.mul_add(y, z)),
+            ] {
+                if !expected.is_nan() {
+                    assert_eq!(
+                        result.get_32_bits(),
+                        expected.to_bits(),
+                        "{:?} {:?} {:?}",
+                        a,
+                        b,
+                        c
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fused_multiply_add() {
+        let fp = FloatingPoint::new_f32;
+        let fma = |a, b, c| fma_fp(a, b, c, FmaMode::Fused).get_32_bits();
+        // for normal operands and results it rounds once to nearest even, as fmaf does
+        let mut rng = rand::thread_rng();
+        let mut random =
+            || FloatingPoint::new(rng.gen::<u32>() & 0x807fffff | rng.gen_range(100..150) << 23);
+        for _ in 0..100000 {
+            let (a, b, c) = (random(), random(), random());
+            let expected = a
+                .get_f32_value()
+                .mul_add(b.get_f32_value(), c.get_f32_value());
+            assert_eq!(fma(a, b, c), expected.to_bits(), "{:?} {:?} {:?}", a, b, c);
+        }
+        // subnormal inputs of the product are flushed to zero as in fmul
+        assert_eq!(fma(fp(1e-40), fp(2.0_f32.powi(100)), fp(0.0)), 0);
+        // the adder gives the special values and the sign of zero
+        assert_eq!(fma(fp(1.0), fp(1.0), fp(-1.0)), 0);
+        assert_eq!(fma(fp(-0.0), fp(1.0), fp(-0.0)), 0x80000000);
+        assert_eq!(fma(fp(f32::INFINITY), fp(0.0), fp(1.0)), 0xffc00000);
+        assert_eq!(
+            fma(fp(f32::INFINITY), fp(1.0), fp(f32::NEG_INFINITY)),
+            0xffc00000
+        );
+        assert_eq!(
+            fma(FloatingPoint::new(0x7f800001), fp(1.0), fp(1.0)),
+            0x7fc00001
+        );
+        assert_eq!(fma(fp(f32::MAX), fp(2.0), fp(0.0)), f32::INFINITY.to_bits());
+    }
 }
@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use crate::types::*;
+
+const READ: usize = 0;
+const WRITE: usize = 1;
+const MISS: usize = 2;
+
+/// Per-block read, write and miss counts over time buckets of a fixed number of instructions.
+/// Only blocks that are accessed get a row, so the image height is the number of touched blocks.
+pub struct Heatmap {
+    block_size: usize,
+    interval: InstructionCount,
+    rows: BTreeMap<Address, Vec<[u64; 3]>>,
+    bucket_num: usize,
+}
+
+impl Heatmap {
+    pub fn new(block_size: usize, interval: InstructionCount) -> Self {
+        Heatmap {
+            block_size,
+            interval,
+            rows: BTreeMap::new(),
+            bucket_num: 0,
+        }
+    }
+
+    pub fn record(
+        &mut self,
+        addr: Address,
+        is_write: bool,
+        hit: bool,
+        inst_count: InstructionCount,
+    ) {
+        let block = addr / self.block_size as Address * self.block_size as Address;
+        let bucket = (inst_count / self.interval) as usize;
+        self.bucket_num = self.bucket_num.max(bucket + 1);
+        let row = self.rows.entry(block).or_default();
+        if row.len() <= bucket {
+            row.resize(bucket + 1, [0; 3]);
+        }
+        row[bucket][if is_write { WRITE } else { READ }] += 1;
+        if !hit {
+            row[bucket][MISS] += 1;
+        }
+    }
+
+    fn get_max_count(&self, kinds: &[usize]) -> u64 {
+        self.rows
+            .values()
+            .flatten()
+            .map(|counts| kinds.iter().map(|&kind| counts[kind]).max().unwrap())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Maps a count to 0-255 on a log scale, so sparse blocks stay visible next to hot ones.
+    fn get_intensity(count: u64, max_count: u64) -> u8 {
+        if count == 0 {
+            0
+        } else {
+            (((count + 1) as f64).ln() / ((max_count + 1) as f64).ln() * 255.0).round() as u8
+        }
+    }
+
+    /// Writes a binary PPM with a row per block and a column per time bucket.
+    /// With `miss` false, reads are green and writes are red; otherwise misses are white.
+    fn write_ppm(&self, writer: &mut impl Write, miss: bool) -> io::Result<()> {
+        let max_count = if miss {
+            self.get_max_count(&[MISS])
+        } else {
+            self.get_max_count(&[READ, WRITE])
+        };
+        write!(writer, "P6\n{} {}\n255\n", self.bucket_num, self.rows.len())?;
+        for row in self.rows.values() {
+            for bucket in 0..self.bucket_num {
+                let counts = row.get(bucket).copied().unwrap_or([0; 3]);
+                let pixel = if miss {
+                    [Self::get_intensity(counts[MISS], max_count); 3]
+                } else {
+                    [
+                        Self::get_intensity(counts[WRITE], max_count),
+                        Self::get_intensity(counts[READ], max_count),
+                        0,
+                    ]
+                };
+                writer.write_all(&pixel)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the access image to `path`, the miss image to `<stem>.miss.ppm` and the row
+    /// addresses and totals to `<stem>.txt`, where `stem` is `path` without `.ppm`.
+    pub fn output_files(&self, path: &str) -> io::Result<()> {
+        let stem = path.strip_suffix(".ppm").unwrap_or(path);
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_ppm(&mut writer, false)?;
+        writer.flush()?;
+        let mut writer = BufWriter::new(File::create(format!("{}.miss.ppm", stem))?);
+        self.write_ppm(&mut writer, true)?;
+        writer.flush()?;
+
+        let mut writer = BufWriter::new(File::create(format!("{}.txt", stem))?);
+        writeln!(
+            writer,
+            "# block size: {} bytes, instructions per column: {}, columns: {}",
+            self.block_size, self.interval, self.bucket_num
+        )?;
+        writeln!(
+            writer,
+            "# max count per pixel: access {}, miss {}",
+            self.get_max_count(&[READ, WRITE]),
+            self.get_max_count(&[MISS])
+        )?;
+        writeln!(writer, "# row address reads writes misses")?;
+        for (row, (block, buckets)) in self.rows.iter().enumerate() {
+            let total = buckets.iter().fold([0; 3], |total, counts| {
+                [
+                    total[READ] + counts[READ],
+                    total[WRITE] + counts[WRITE],
+                    total[MISS] + counts[MISS],
+                ]
+            });
+            writeln!(
+                writer,
+                "{} {:#010x} {} {} {}",
+                row, block, total[READ], total[WRITE], total[MISS]
+            )?;
+        }
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut heatmap = Heatmap::new(4096, 10);
+        heatmap.record(0x1004, false, true, 0);
+        heatmap.record(0x1ffc, true, false, 3);
+        heatmap.record(0x7fff_f000, false, false, 25);
+        assert_eq!(heatmap.bucket_num, 3);
+        assert_eq!(heatmap.rows[&0x1000], vec![[1, 1, 1]]);
+        assert_eq!(heatmap.rows[&0x7fff_f000], vec![[0; 3], [0; 3], [1, 0, 1]]);
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let mut heatmap = Heatmap::new(16, 1);
+        heatmap.record(0, false, true, 0);
+        heatmap.record(32, true, false, 1);
+        let mut buf = vec![];
+        heatmap.write_ppm(&mut buf, false).unwrap();
+        let header = b"P6\n2 2\n255\n";
+        assert_eq!(&buf[..header.len()], header);
+        assert_eq!(
+            &buf[header.len()..],
+            &[0, 255, 0, 0, 0, 0, 0, 0, 0, 255, 0, 0]
+        );
+    }
+}
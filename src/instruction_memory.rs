@@ -2,13 +2,13 @@ use crate::types::*;
 pub const INSTRUCTION_MEMORY_SIZE: usize = 4 * 1024 * 1024;
 
 pub struct InstructionMemory {
-    values: [InstructionValue; INSTRUCTION_MEMORY_SIZE],
+    values: Vec<InstructionValue>,
 }
 
 impl InstructionMemory {
     pub fn new() -> Self {
         let init_val = 0;
-        let values = [init_val; INSTRUCTION_MEMORY_SIZE];
+        let values = vec![init_val; INSTRUCTION_MEMORY_SIZE];
         InstructionMemory { values }
     }
 
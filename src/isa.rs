@@ -0,0 +1,1228 @@
+use crate::compressed::*;
+use crate::core::*;
+use crate::csr::*;
+use crate::decoder::*;
+use crate::fpu_emulator::*;
+use crate::instruction::*;
+use crate::syscall::*;
+use crate::types::*;
+use crate::utils::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    I,
+    R,
+    S,
+    B,
+    J,
+    U,
+    R4,
+}
+
+/// Functional unit of an instruction. Only the multiplier and the divider have
+/// a configurable latency, everything else finishes in one cycle.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Alu,
+    Multiplier,
+    Divider,
+}
+
+/// Operand kinds and behavior of an instruction. The variant decides which registers
+/// are read and written, the pipeline stage that does the work and the disassembly layout;
+/// the function is the part that differs between instructions of the same kind.
+#[derive(Clone, Copy)]
+pub enum Semantics {
+    /// `op xd, xs1, xs2`
+    IntR(&'static (dyn Fn(Int, Int) -> Int + Sync)),
+    /// `op xd, xs1, imm`
+    IntI(&'static (dyn Fn(Int, i32) -> Int + Sync)),
+    /// `op xd, xs1, shamt`, with the shift amount in the low 5 bits of the immediate
+    Shift(&'static (dyn Fn(Int, u32) -> Int + Sync)),
+    /// `op xd, imm`, with the 20-bit immediate and the pc of the instruction
+    Upper(&'static (dyn Fn(i32, Address) -> Int + Sync)),
+    /// `op xd, imm(xs1)`
+    Load(&'static (dyn Fn(&mut Core, Address) -> Int + Sync)),
+    /// `op fd, imm(xs1)`
+    FloatLoad(&'static (dyn Fn(&mut Core, Address) -> FloatingPoint + Sync)),
+    /// `op xs2, imm(xs1)`
+    Store(&'static (dyn Fn(&mut Core, Address, Int) + Sync)),
+    /// `op fs2, imm(xs1)`
+    FloatStore(&'static (dyn Fn(&mut Core, Address, FloatingPoint) + Sync)),
+    /// `op xs1, xs2, pc + (imm << 1)`
+    Branch(&'static (dyn Fn(Int, Int) -> bool + Sync)),
+    /// `op fs1, fs2, pc + (imm << 1)`
+    FloatBranch(&'static (dyn Fn(FloatingPoint, FloatingPoint) -> bool + Sync)),
+    /// `op xd, imm`, jumping to the address computed from the pc and the immediate
+    Jump(&'static (dyn Fn(Address, i32) -> Address + Sync)),
+    /// `op xd, xs1, imm`, jumping to the address computed from xs1 and the immediate
+    JumpRegister(&'static (dyn Fn(Int, i32) -> Address + Sync)),
+    /// `op fd, fs1, fs2`, with the core to read the FPU tables from and raise fflags on
+    FloatR(&'static (dyn Fn(&mut Core, FloatingPoint, FloatingPoint) -> FloatingPoint + Sync)),
+    /// `op fd, fs1`
+    FloatUnary(&'static (dyn Fn(&mut Core, FloatingPoint) -> FloatingPoint + Sync)),
+    /// `op xd, fs1, fs2`
+    FloatCompare(&'static (dyn Fn(&mut Core, FloatingPoint, FloatingPoint) -> Int + Sync)),
+    /// `op xd, fs1`
+    FloatToInt(&'static (dyn Fn(FloatingPoint) -> Int + Sync)),
+    /// `op fd, xs1`
+    IntToFloat(&'static (dyn Fn(Int) -> FloatingPoint + Sync)),
+    /// `op xd, fs1, rm`, with the rm field (or frm for dyn) resolved to a rounding mode
+    FloatToIntRounded(&'static (dyn Fn(&mut Core, FloatingPoint, RoundingMode) -> Int + Sync)),
+    /// `op fd, xs1, rm`
+    IntToFloatRounded(&'static (dyn Fn(&mut Core, Int, RoundingMode) -> FloatingPoint + Sync)),
+    /// `op fd, fs1, fs2, rm`
+    FloatRRounded(
+        &'static (dyn Fn(&mut Core, FloatingPoint, FloatingPoint, RoundingMode) -> FloatingPoint
+                      + Sync),
+    ),
+    /// `op fd, fs1, rm`
+    FloatUnaryRounded(
+        &'static (dyn Fn(&mut Core, FloatingPoint, RoundingMode) -> FloatingPoint + Sync),
+    ),
+    /// `op fd, fs1, fs2, fs3`
+    FloatR4(
+        &'static (dyn Fn(&mut Core, FloatingPoint, FloatingPoint, FloatingPoint) -> FloatingPoint
+                      + Sync),
+    ),
+    /// `op fd, fs1, fs2, fs3, rm`
+    FloatR4Rounded(
+        &'static (dyn Fn(
+            &mut Core,
+            FloatingPoint,
+            FloatingPoint,
+            FloatingPoint,
+            RoundingMode,
+        ) -> FloatingPoint
+                      + Sync),
+    ),
+    /// `op xd`, reading from the input
+    IntInput(&'static (dyn Fn(&mut Core) -> Int + Sync)),
+    /// `op fd`, reading from the input
+    FloatInput(&'static (dyn Fn(&mut Core) -> FloatingPoint + Sync)),
+    /// `op xs2`, writing to the output
+    Output(&'static (dyn Fn(&mut Core, Int) + Sync)),
+    /// `op`, run in the write back stage after every older instruction has finished
+    System(&'static (dyn Fn(&mut Core) + Sync)),
+    /// `op`, run in the exec stage like `EnvironmentCall`, so no younger instruction takes effect
+    Breakpoint(&'static (dyn Fn(&mut Core) + Sync)),
+    /// `op xd, csr, xs1`, writing the old value of the CSR to xd and updating the CSR with xs1
+    /// (not written by csrrs and csrrc with xs1 of x0)
+    Csr(CsrUpdate),
+    /// `op xd, csr, uimm`, with the 5-bit unsigned immediate in the rs1 field
+    /// (not written by csrrsi and csrrci with uimm of 0)
+    CsrImmediate(CsrUpdate),
+    /// `op`, calling the environment with a7 and a0 to a2 and writing the result to a0
+    EnvironmentCall(&'static (dyn Fn(&mut Core, Int, [Int; 3]) -> Int + Sync)),
+}
+
+/// One entry of the ISA table. `funct3`, `funct7` and `rs2` of `None` match any value;
+/// for I-format instructions `funct7` is the upper 7 bits of the immediate and `rs2`
+/// is the lower 5 bits, and for R4-format instructions `funct7` is `funct2`.
+pub struct InstructionDef {
+    pub name: &'static str,
+    pub format: Format,
+    pub op: Op,
+    pub funct3: Option<Funct3>,
+    pub funct7: Option<Funct7>,
+    pub rs2: Option<Rs2>,
+    pub semantics: Semantics,
+    pub unit: Unit,
+}
+
+impl InstructionDef {
+    pub const fn new(
+        name: &'static str,
+        format: Format,
+        op: Op,
+        funct3: Option<Funct3>,
+        funct7: Option<Funct7>,
+        semantics: Semantics,
+    ) -> Self {
+        InstructionDef {
+            name,
+            format,
+            op,
+            funct3,
+            funct7,
+            rs2: None,
+            semantics,
+            unit: Unit::Alu,
+        }
+    }
+
+    pub const fn on(self, unit: Unit) -> Self {
+        InstructionDef { unit, ..self }
+    }
+
+    /// Restricts the entry to one value of the rs2 field, for instructions that use it as
+    /// part of the opcode.
+    pub const fn with_rs2(self, rs2: Rs2) -> Self {
+        InstructionDef {
+            rs2: Some(rs2),
+            ..self
+        }
+    }
+
+    fn matches(&self, format: Format, funct3: Funct3, funct7: Funct7, rs2: Rs2) -> bool {
+        self.format == format
+            && self.funct3.is_none_or(|expected| expected == funct3)
+            && self.funct7.is_none_or(|expected| expected == funct7)
+            && self.rs2.is_none_or(|expected| expected == rs2)
+    }
+}
+
+pub fn branch_target(origin_pc: Address, imm: i32) -> Address {
+    (origin_pc as i32).wrapping_add(imm << 1) as Address
+}
+
+/// Returns the result of an operation on floats after accumulating its exception flags in fflags.
+fn with_flags<T>(core: &mut Core, (result, flags): (T, FpFlags)) -> T {
+    core.raise_fp_flags(flags);
+    result
+}
+
+/// Instructions shared by every ISA.
+pub static INSTRUCTIONS: &[InstructionDef] = {
+    use Format::*;
+    use Semantics::*;
+    &[
+        InstructionDef::new(
+            "lb",
+            I,
+            3,
+            Some(0b000),
+            None,
+            Load(&|core, addr| core.load_byte(addr) as Int),
+        ),
+        InstructionDef::new(
+            "lh",
+            I,
+            3,
+            Some(0b001),
+            None,
+            Load(&|core, addr| core.load_half(addr) as Int),
+        ),
+        InstructionDef::new("lw", I, 3, Some(0b010), None, Load(&Core::load_word)),
+        InstructionDef::new(
+            "lbu",
+            I,
+            3,
+            Some(0b100),
+            None,
+            Load(&|core, addr| core.load_ubyte(addr) as Int),
+        ),
+        InstructionDef::new(
+            "lhu",
+            I,
+            3,
+            Some(0b101),
+            None,
+            Load(&|core, addr| core.load_uhalf(addr) as Int),
+        ),
+        InstructionDef::new(
+            "flw",
+            I,
+            7,
+            Some(0b010),
+            None,
+            FloatLoad(&|core, addr| FloatingPoint::new(i32_to_u32(core.load_word_fp(addr)))),
+        ),
+        InstructionDef::new(
+            "addi",
+            I,
+            19,
+            Some(0b000),
+            None,
+            IntI(&|a, imm| a.wrapping_add(imm)),
+        ),
+        InstructionDef::new(
+            "slli",
+            I,
+            19,
+            Some(0b001),
+            Some(0b0000000),
+            Shift(&|a, shamt| a << shamt),
+        ),
+        InstructionDef::new(
+            "slti",
+            I,
+            19,
+            Some(0b010),
+            None,
+            IntI(&|a, imm| (a < imm) as Int),
+        ),
+        // the immediate is sign-extended and then compared as unsigned
+        InstructionDef::new(
+            "sltiu",
+            I,
+            19,
+            Some(0b011),
+            None,
+            IntI(&|a, imm| (i32_to_u32(a) < i32_to_u32(imm)) as Int),
+        ),
+        InstructionDef::new("xori", I, 19, Some(0b100), None, IntI(&|a, imm| a ^ imm)),
+        InstructionDef::new(
+            "srli",
+            I,
+            19,
+            Some(0b101),
+            Some(0b0000000),
+            Shift(&|a, shamt| u32_to_i32(i32_to_u32(a) >> shamt)),
+        ),
+        InstructionDef::new(
+            "srai",
+            I,
+            19,
+            Some(0b101),
+            Some(0b0100000),
+            Shift(&|a, shamt| a >> shamt),
+        ),
+        InstructionDef::new("ori", I, 19, Some(0b110), None, IntI(&|a, imm| a | imm)),
+        InstructionDef::new("andi", I, 19, Some(0b111), None, IntI(&|a, imm| a & imm)),
+        InstructionDef::new(
+            "auipc",
+            U,
+            23,
+            None,
+            None,
+            Upper(&|imm, pc| (imm << 12).wrapping_add(pc as i32)),
+        ),
+        InstructionDef::new(
+            "sb",
+            S,
+            35,
+            Some(0b000),
+            None,
+            Store(&|core, addr, value| core.store_byte(addr, (value & 0xff) as Byte)),
+        ),
+        InstructionDef::new(
+            "sh",
+            S,
+            35,
+            Some(0b001),
+            None,
+            Store(&|core, addr, value| core.store_half(addr, (value & 0xffff) as Half)),
+        ),
+        InstructionDef::new("sw", S, 35, Some(0b010), None, Store(&Core::store_word)),
+        InstructionDef::new(
+            "fsw",
+            S,
+            39,
+            Some(0b010),
+            None,
+            FloatStore(&|core, addr, value| core.store_word(addr, u32_to_i32(value.get_32_bits()))),
+        ),
+        InstructionDef::new(
+            "add",
+            R,
+            51,
+            Some(0b000),
+            Some(0b0000000),
+            IntR(&|a, b| a.wrapping_add(b)),
+        ),
+        InstructionDef::new(
+            "sub",
+            R,
+            51,
+            Some(0b000),
+            Some(0b0100000),
+            IntR(&|a, b| a.wrapping_sub(b)),
+        ),
+        InstructionDef::new(
+            "sll",
+            R,
+            51,
+            Some(0b001),
+            Some(0b0000000),
+            IntR(&|a, b| u32_to_i32(i32_to_u32(a) << (b & 0x1f))),
+        ),
+        InstructionDef::new(
+            "slt",
+            R,
+            51,
+            Some(0b010),
+            Some(0b0000000),
+            IntR(&|a, b| (a < b) as Int),
+        ),
+        InstructionDef::new(
+            "sltu",
+            R,
+            51,
+            Some(0b011),
+            Some(0b0000000),
+            IntR(&|a, b| (i32_to_u32(a) < i32_to_u32(b)) as Int),
+        ),
+        InstructionDef::new(
+            "xor",
+            R,
+            51,
+            Some(0b100),
+            Some(0b0000000),
+            IntR(&|a, b| a ^ b),
+        ),
+        InstructionDef::new(
+            "srl",
+            R,
+            51,
+            Some(0b101),
+            Some(0b0000000),
+            IntR(&|a, b| u32_to_i32(i32_to_u32(a) >> (b & 0x1f))),
+        ),
+        InstructionDef::new(
+            "sra",
+            R,
+            51,
+            Some(0b101),
+            Some(0b0100000),
+            IntR(&|a, b| a >> (b & 0x1f)),
+        ),
+        InstructionDef::new(
+            "or",
+            R,
+            51,
+            Some(0b110),
+            Some(0b0000000),
+            IntR(&|a, b| a | b),
+        ),
+        InstructionDef::new(
+            "and",
+            R,
+            51,
+            Some(0b111),
+            Some(0b0000000),
+            IntR(&|a, b| a & b),
+        ),
+        InstructionDef::new(
+            "mul",
+            R,
+            51,
+            Some(0b000),
+            Some(0b0000001),
+            IntR(&|a, b| a.wrapping_mul(b)),
+        )
+        .on(Unit::Multiplier),
+        InstructionDef::new(
+            "mulh",
+            R,
+            51,
+            Some(0b001),
+            Some(0b0000001),
+            IntR(&|a, b| ((a as i64 * b as i64) >> 32) as Int),
+        )
+        .on(Unit::Multiplier),
+        InstructionDef::new(
+            "mulhsu",
+            R,
+            51,
+            Some(0b010),
+            Some(0b0000001),
+            IntR(&|a, b| ((a as i64 * i32_to_u32(b) as i64) >> 32) as Int),
+        )
+        .on(Unit::Multiplier),
+        InstructionDef::new(
+            "mulhu",
+            R,
+            51,
+            Some(0b011),
+            Some(0b0000001),
+            IntR(&|a, b| ((i32_to_u32(a) as u64 * i32_to_u32(b) as u64) >> 32) as Int),
+        )
+        .on(Unit::Multiplier),
+        // division by zero gives -1 and overflow (-2^31 / -1) gives -2^31
+        InstructionDef::new(
+            "div",
+            R,
+            51,
+            Some(0b100),
+            Some(0b0000001),
+            IntR(&|a, b| if b == 0 { -1 } else { a.wrapping_div(b) }),
+        )
+        .on(Unit::Divider),
+        // division by zero gives 2^32 - 1
+        InstructionDef::new(
+            "divu",
+            R,
+            51,
+            Some(0b101),
+            Some(0b0000001),
+            IntR(&|a, b| u32_to_i32(i32_to_u32(a).checked_div(i32_to_u32(b)).unwrap_or(u32::MAX))),
+        )
+        .on(Unit::Divider),
+        // division by zero gives the dividend and overflow (-2^31 % -1) gives 0
+        InstructionDef::new(
+            "rem",
+            R,
+            51,
+            Some(0b110),
+            Some(0b0000001),
+            IntR(&|a, b| if b == 0 { a } else { a.wrapping_rem(b) }),
+        )
+        .on(Unit::Divider),
+        InstructionDef::new(
+            "remu",
+            R,
+            51,
+            Some(0b111),
+            Some(0b0000001),
+            IntR(&|a, b| {
+                u32_to_i32(
+                    i32_to_u32(a)
+                        .checked_rem(i32_to_u32(b))
+                        .unwrap_or(i32_to_u32(a)),
+                )
+            }),
+        )
+        .on(Unit::Divider),
+        InstructionDef::new("lui", U, 55, None, None, Upper(&|imm, _| imm << 12)),
+        InstructionDef::new(
+            "fsgnj",
+            R,
+            83,
+            Some(0b000),
+            Some(0b0010000),
+            FloatR(&|_, a, b| fp_sign_injection(a, b)),
+        ),
+        InstructionDef::new(
+            "fsgnjn",
+            R,
+            83,
+            Some(0b001),
+            Some(0b0010000),
+            FloatR(&|_, a, b| fp_negative_sign_injection(a, b)),
+        ),
+        InstructionDef::new(
+            "fsgnjx",
+            R,
+            83,
+            Some(0b010),
+            Some(0b0010000),
+            FloatR(&|_, a, b| fp_xor_sign_injection(a, b)),
+        ),
+        InstructionDef::new(
+            "fcvt.wu.s",
+            R,
+            83,
+            None,
+            Some(0b1100000),
+            FloatToIntRounded(&|core, a, rm| with_flags(core, fp_to_int_rounded(a, rm, false))),
+        )
+        .with_rs2(1),
+        InstructionDef::new(
+            "fmv.x.w",
+            R,
+            83,
+            Some(0b000),
+            Some(0b1110000),
+            FloatToInt(&|a| u32_to_i32(a.get_32_bits())),
+        ),
+        InstructionDef::new(
+            "fclass",
+            R,
+            83,
+            Some(0b001),
+            Some(0b1110000),
+            FloatToInt(&fp_class),
+        ),
+        InstructionDef::new(
+            "fcvt.s.wu",
+            R,
+            83,
+            None,
+            Some(0b1101000),
+            IntToFloatRounded(&|core, a, rm| {
+                with_flags(core, int_to_fp_rounded(i32_to_u32(a) as i64, rm))
+            }),
+        )
+        .with_rs2(1),
+        InstructionDef::new(
+            "fmv.w.x",
+            R,
+            83,
+            Some(0b000),
+            Some(0b1111000),
+            IntToFloat(&|a| FloatingPoint::new(i32_to_u32(a))),
+        ),
+        InstructionDef::new("beq", B, 99, Some(0b000), None, Branch(&|a, b| a == b)),
+        InstructionDef::new("bne", B, 99, Some(0b001), None, Branch(&|a, b| a != b)),
+        InstructionDef::new("blt", B, 99, Some(0b100), None, Branch(&|a, b| a < b)),
+        InstructionDef::new("bge", B, 99, Some(0b101), None, Branch(&|a, b| a >= b)),
+        InstructionDef::new(
+            "bltu",
+            B,
+            99,
+            Some(0b110),
+            None,
+            Branch(&|a, b| i32_to_u32(a) < i32_to_u32(b)),
+        ),
+        InstructionDef::new(
+            "bgeu",
+            B,
+            99,
+            Some(0b111),
+            None,
+            Branch(&|a, b| i32_to_u32(a) >= i32_to_u32(b)),
+        ),
+        InstructionDef::new("jal", J, 111, None, None, Jump(&branch_target)),
+        InstructionDef::new("csrrw", I, 115, Some(0b001), None, Csr(CsrUpdate::Write)),
+        InstructionDef::new("csrrs", I, 115, Some(0b010), None, Csr(CsrUpdate::Set)),
+        InstructionDef::new("csrrc", I, 115, Some(0b011), None, Csr(CsrUpdate::Clear)),
+        InstructionDef::new(
+            "csrrwi",
+            I,
+            115,
+            Some(0b101),
+            None,
+            CsrImmediate(CsrUpdate::Write),
+        ),
+        InstructionDef::new(
+            "csrrsi",
+            I,
+            115,
+            Some(0b110),
+            None,
+            CsrImmediate(CsrUpdate::Set),
+        ),
+        InstructionDef::new(
+            "csrrci",
+            I,
+            115,
+            Some(0b111),
+            None,
+            CsrImmediate(CsrUpdate::Clear),
+        ),
+    ]
+};
+
+/// Instructions of the cpuex2 dialect: float arithmetic with the cpuex2 FPU, jalr scaling
+/// the immediate by 2 like the branches, and float branches and I/O on the custom opcodes.
+/// fcvt.w.s and fcvt.s.w with rm of 0b000, which existing binaries use, are the legacy
+/// conversions of the FPU, which round half away from zero and raise no flags. rm of 0b000
+/// is not rne here; the other rounding modes, and dyn with any frm, convert as in the F
+/// extension.
+pub static CPUEX2_INSTRUCTIONS: &[InstructionDef] = {
+    use Format::*;
+    use Semantics::*;
+    &[
+        InstructionDef::new(
+            "fmadd",
+            R4,
+            67,
+            None,
+            Some(0b00),
+            FloatR4(&|core, a, b, c| fma_fp(a, b, c, core.get_fma_mode())),
+        ),
+        InstructionDef::new(
+            "fmsub",
+            R4,
+            71,
+            None,
+            Some(0b00),
+            FloatR4(&|core, a, b, c| fma_fp(a, b, -c, core.get_fma_mode())),
+        ),
+        InstructionDef::new(
+            "fnmsub",
+            R4,
+            75,
+            None,
+            Some(0b00),
+            FloatR4(&|core, a, b, c| fma_fp(-a, b, c, core.get_fma_mode())),
+        ),
+        InstructionDef::new(
+            "fnmadd",
+            R4,
+            79,
+            None,
+            Some(0b00),
+            FloatR4(&|core, a, b, c| fma_fp(-a, b, -c, core.get_fma_mode())),
+        ),
+        InstructionDef::new(
+            "fadd",
+            R,
+            83,
+            None,
+            Some(0b0000000),
+            FloatR(&|_, a, b| a + b),
+        ),
+        InstructionDef::new(
+            "fsub",
+            R,
+            83,
+            None,
+            Some(0b0000100),
+            FloatR(&|_, a, b| a - b),
+        ),
+        InstructionDef::new(
+            "fmul",
+            R,
+            83,
+            None,
+            Some(0b0001000),
+            FloatR(&|_, a, b| a * b),
+        ),
+        InstructionDef::new(
+            "fdiv",
+            R,
+            83,
+            None,
+            Some(0b0001100),
+            FloatR(&|core, a, b| div_fp(a, b, core.get_inv_map())),
+        ),
+        InstructionDef::new(
+            "fsqrt",
+            R,
+            83,
+            None,
+            Some(0b0101100),
+            FloatUnary(&|core, a| sqrt_fp(a, core.get_sqrt_map())),
+        ),
+        InstructionDef::new(
+            "fmin",
+            R,
+            83,
+            Some(0b000),
+            Some(0b0010100),
+            FloatR(&|_, a, b| if a < b { a } else { b }),
+        ),
+        InstructionDef::new(
+            "fmax",
+            R,
+            83,
+            Some(0b001),
+            Some(0b0010100),
+            FloatR(&|_, a, b| if a > b { a } else { b }),
+        ),
+        InstructionDef::new(
+            "feq",
+            R,
+            83,
+            Some(0b010),
+            Some(0b1010000),
+            FloatCompare(&|_, a, b| (a == b) as Int),
+        ),
+        InstructionDef::new(
+            "flt",
+            R,
+            83,
+            Some(0b001),
+            Some(0b1010000),
+            FloatCompare(&|_, a, b| (a < b) as Int),
+        ),
+        InstructionDef::new(
+            "fle",
+            R,
+            83,
+            Some(0b000),
+            Some(0b1010000),
+            FloatCompare(&|_, a, b| (a <= b) as Int),
+        ),
+        InstructionDef::new(
+            "fcvt.w.s",
+            R,
+            83,
+            Some(0b000),
+            Some(0b1100000),
+            FloatToInt(&fp_to_int),
+        )
+        .with_rs2(0),
+        InstructionDef::new(
+            "fcvt.w.s",
+            R,
+            83,
+            None,
+            Some(0b1100000),
+            FloatToIntRounded(&|core, a, rm| with_flags(core, fp_to_int_rounded(a, rm, true))),
+        )
+        .with_rs2(0),
+        InstructionDef::new(
+            "fcvt.s.w",
+            R,
+            83,
+            Some(0b000),
+            Some(0b1101000),
+            IntToFloat(&int_to_fp),
+        )
+        .with_rs2(0),
+        InstructionDef::new(
+            "fcvt.s.w",
+            R,
+            83,
+            None,
+            Some(0b1101000),
+            IntToFloatRounded(&|core, a, rm| with_flags(core, int_to_fp_rounded(a as i64, rm))),
+        )
+        .with_rs2(0),
+        InstructionDef::new(
+            "fbeq",
+            B,
+            100,
+            Some(0b000),
+            None,
+            FloatBranch(&|a, b| a == b),
+        ),
+        InstructionDef::new(
+            "fbne",
+            B,
+            100,
+            Some(0b001),
+            None,
+            FloatBranch(&|a, b| a != b),
+        ),
+        InstructionDef::new(
+            "fblt",
+            B,
+            100,
+            Some(0b100),
+            None,
+            FloatBranch(&|a, b| a < b),
+        ),
+        InstructionDef::new(
+            "fble",
+            B,
+            100,
+            Some(0b101),
+            None,
+            FloatBranch(&|a, b| a <= b),
+        ),
+        InstructionDef::new(
+            "jalr",
+            I,
+            103,
+            Some(0b000),
+            None,
+            JumpRegister(&|a, imm| a.wrapping_add(imm << 1) as Address),
+        ),
+        InstructionDef::new("end", I, 115, Some(0b000), None, System(&Core::end)),
+        InstructionDef::new("in", I, 116, Some(0b000), None, IntInput(&Core::read_int)),
+        InstructionDef::new(
+            "fin",
+            I,
+            116,
+            Some(0b001),
+            None,
+            FloatInput(&|core| FloatingPoint::new(i32_to_u32(core.read_float()))),
+        ),
+        InstructionDef::new(
+            "outchar",
+            S,
+            117,
+            Some(0b000),
+            None,
+            Output(&Core::print_char),
+        ),
+    ]
+};
+
+/// Instructions of standard RV32IMF: IEEE 754 float arithmetic and conversions rounded with
+/// the rm field (or frm for dyn) and raising exception flags, fused multiply-add, jalr clearing
+/// the lowest bit of `rs1 + imm`, fence, and ecall and ebreak in place of `end`.
+pub static RV32IMF_INSTRUCTIONS: &[InstructionDef] = {
+    use Format::*;
+    use Semantics::*;
+    &[
+        InstructionDef::new(
+            "fmadd",
+            R4,
+            67,
+            None,
+            Some(0b00),
+            FloatR4Rounded(&|core, a, b, c, rm| with_flags(core, ieee_fma(a, b, c, rm))),
+        ),
+        InstructionDef::new(
+            "fmsub",
+            R4,
+            71,
+            None,
+            Some(0b00),
+            FloatR4Rounded(&|core, a, b, c, rm| with_flags(core, ieee_fma(a, b, -c, rm))),
+        ),
+        InstructionDef::new(
+            "fnmsub",
+            R4,
+            75,
+            None,
+            Some(0b00),
+            FloatR4Rounded(&|core, a, b, c, rm| with_flags(core, ieee_fma(-a, b, c, rm))),
+        ),
+        InstructionDef::new(
+            "fnmadd",
+            R4,
+            79,
+            None,
+            Some(0b00),
+            FloatR4Rounded(&|core, a, b, c, rm| with_flags(core, ieee_fma(-a, b, -c, rm))),
+        ),
+        InstructionDef::new(
+            "fadd",
+            R,
+            83,
+            None,
+            Some(0b0000000),
+            FloatRRounded(&|core, a, b, rm| with_flags(core, ieee_add(a, b, rm))),
+        ),
+        InstructionDef::new(
+            "fsub",
+            R,
+            83,
+            None,
+            Some(0b0000100),
+            FloatRRounded(&|core, a, b, rm| with_flags(core, ieee_add(a, -b, rm))),
+        ),
+        InstructionDef::new(
+            "fmul",
+            R,
+            83,
+            None,
+            Some(0b0001000),
+            FloatRRounded(&|core, a, b, rm| with_flags(core, ieee_mul(a, b, rm))),
+        ),
+        InstructionDef::new(
+            "fdiv",
+            R,
+            83,
+            None,
+            Some(0b0001100),
+            FloatRRounded(&|core, a, b, rm| with_flags(core, ieee_div(a, b, rm))),
+        ),
+        InstructionDef::new(
+            "fsqrt",
+            R,
+            83,
+            None,
+            Some(0b0101100),
+            FloatUnaryRounded(&|core, a, rm| with_flags(core, ieee_sqrt(a, rm))),
+        ),
+        InstructionDef::new(
+            "fmin",
+            R,
+            83,
+            Some(0b000),
+            Some(0b0010100),
+            FloatR(&|core, a, b| with_flags(core, ieee_min_max(a, b, false))),
+        ),
+        InstructionDef::new(
+            "fmax",
+            R,
+            83,
+            Some(0b001),
+            Some(0b0010100),
+            FloatR(&|core, a, b| with_flags(core, ieee_min_max(a, b, true))),
+        ),
+        InstructionDef::new(
+            "feq",
+            R,
+            83,
+            Some(0b010),
+            Some(0b1010000),
+            FloatCompare(&|core, a, b| with_flags(core, ieee_compare(a, b, false, |a, b| a == b))),
+        ),
+        InstructionDef::new(
+            "flt",
+            R,
+            83,
+            Some(0b001),
+            Some(0b1010000),
+            FloatCompare(&|core, a, b| with_flags(core, ieee_compare(a, b, true, |a, b| a < b))),
+        ),
+        InstructionDef::new(
+            "fle",
+            R,
+            83,
+            Some(0b000),
+            Some(0b1010000),
+            FloatCompare(&|core, a, b| with_flags(core, ieee_compare(a, b, true, |a, b| a <= b))),
+        ),
+        InstructionDef::new(
+            "fcvt.w.s",
+            R,
+            83,
+            None,
+            Some(0b1100000),
+            FloatToIntRounded(&|core, a, rm| with_flags(core, fp_to_int_rounded(a, rm, true))),
+        )
+        .with_rs2(0),
+        InstructionDef::new(
+            "fcvt.s.w",
+            R,
+            83,
+            None,
+            Some(0b1101000),
+            IntToFloatRounded(&|core, a, rm| with_flags(core, int_to_fp_rounded(a as i64, rm))),
+        )
+        .with_rs2(0),
+        // a no-op, since every load and store takes effect in program order
+        InstructionDef::new("fence", I, 15, Some(0b000), None, System(&|_| {})),
+        InstructionDef::new(
+            "jalr",
+            I,
+            103,
+            Some(0b000),
+            None,
+            JumpRegister(&|a, imm| (a.wrapping_add(imm) & !1) as Address),
+        ),
+        InstructionDef::new(
+            "ecall",
+            I,
+            115,
+            Some(0b000),
+            Some(0b0000000),
+            EnvironmentCall(&system_call),
+        )
+        .with_rs2(0),
+        InstructionDef::new(
+            "ebreak",
+            I,
+            115,
+            Some(0b000),
+            Some(0b0000000),
+            Breakpoint(&Core::breakpoint),
+        )
+        .with_rs2(1),
+    ]
+};
+
+/// Instruction set to simulate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Isa {
+    /// The cpuex2 dialect, with the cpuex2 FPU and float branches, end and I/O on ops 100, 115, 116 and 117
+    Cpuex2,
+    /// Standard RV32IMF as in the official spec, which binaries from the GNU RISC-V toolchain run on
+    Rv32imf,
+}
+
+impl Isa {
+    fn get_instructions(self) -> &'static [InstructionDef] {
+        match self {
+            Isa::Cpuex2 => CPUEX2_INSTRUCTIONS,
+            Isa::Rv32imf => RV32IMF_INSTRUCTIONS,
+        }
+    }
+}
+
+/// Decoder built from the ISA table, with the entries grouped by opcode.
+/// Registered instructions are tried before the built-in ones, so they can also
+/// replace an existing encoding. With the C extension, 16-bit instructions are
+/// expanded to the 32-bit instructions they stand for.
+#[derive(Clone)]
+pub struct InstructionSet {
+    defs_by_op: Vec<Vec<&'static InstructionDef>>,
+    compressed: bool,
+}
+
+impl InstructionSet {
+    pub fn new(isa: Isa) -> Self {
+        let mut defs_by_op = vec![vec![]; 128];
+        for def in INSTRUCTIONS.iter().chain(isa.get_instructions()) {
+            defs_by_op[def.op as usize].push(def);
+        }
+        InstructionSet {
+            defs_by_op,
+            compressed: false,
+        }
+    }
+
+    pub fn enable_compressed(&mut self) {
+        self.compressed = true;
+    }
+
+    /// Length in bytes of the instruction whose lowest bits are in `inst`.
+    pub fn get_instruction_length(&self, inst: InstructionValue) -> Address {
+        if self.compressed && is_compressed(inst) {
+            2
+        } else {
+            4
+        }
+    }
+
+    pub fn register(&mut self, def: InstructionDef) -> Result<(), String> {
+        if def.op >= 128 {
+            return Err(format!(
+                "{}: op {} does not fit in 7 bits",
+                def.name, def.op
+            ));
+        }
+        let defs = &mut self.defs_by_op[def.op as usize];
+        if let Some(existing) = defs.iter().find(|existing| existing.format != def.format) {
+            return Err(format!(
+                "{}: op {} is already used by {:?}-format {}",
+                def.name, def.op, existing.format, existing.name
+            ));
+        }
+        defs.insert(0, Box::leak(Box::new(def)));
+        Ok(())
+    }
+
+    /// Decodes `inst` with the format of the instructions on its opcode, so an opcode that
+    /// the ISA leaves free can be taken by a registered instruction of any format.
+    pub fn decode_instruction(&self, inst: InstructionValue) -> Instruction {
+        if self.compressed && is_compressed(inst) {
+            return expand_compressed_instruction(inst & 0xffff);
+        }
+        let decoded = match self.defs_by_op[(inst & 127) as usize].first() {
+            Some(def) => decode_instruction_in_format(inst, def.format),
+            None => decode_instruction(inst),
+        };
+        // a known opcode with funct3/funct7 bits that no instruction uses
+        if self.find_def(decoded).is_none() {
+            return Instruction::Other;
+        }
+        decoded
+    }
+
+    fn find(
+        &self,
+        format: Format,
+        op: Op,
+        funct3: Funct3,
+        funct7: Funct7,
+        rs2: Rs2,
+    ) -> Option<&'static InstructionDef> {
+        self.defs_by_op[op as usize]
+            .iter()
+            .find(|def| def.matches(format, funct3, funct7, rs2))
+            .copied()
+    }
+
+    /// Finds the definition of `inst` and extracts its operands, or returns `None` if it
+    /// is not an instruction of this set.
+    fn find_def(&self, inst: Instruction) -> Option<(&'static InstructionDef, Operands)> {
+        let mut operands = Operands::default();
+        let (format, op, funct3, funct7, rs2) = match inst {
+            Instruction::I(imm, rs1, funct3, rd, op) => {
+                operands.rs1 = rs1;
+                operands.rd = rd;
+                operands.imm = sign_extention_i16(imm, 12) as i32;
+                let funct7 = ((imm >> 5) & 0b1111111) as Funct7;
+                (Format::I, op, funct3, funct7, (imm & 0b11111) as Rs2)
+            }
+            Instruction::R(funct7, rs2, rs1, funct3, rd, op) => {
+                operands.rs2 = rs2;
+                operands.rs1 = rs1;
+                operands.rd = rd;
+                operands.rm = funct3;
+                (Format::R, op, funct3, funct7, rs2)
+            }
+            Instruction::S(imm, rs2, rs1, funct3, op) => {
+                operands.rs2 = rs2;
+                operands.rs1 = rs1;
+                operands.imm = sign_extention_i16(imm, 12) as i32;
+                (Format::S, op, funct3, 0, rs2)
+            }
+            Instruction::B(imm, rs2, rs1, funct3, op) => {
+                operands.rs2 = rs2;
+                operands.rs1 = rs1;
+                operands.imm = sign_extention_i16(imm, 12) as i32;
+                (Format::B, op, funct3, 0, rs2)
+            }
+            Instruction::J(imm, rd, op) => {
+                operands.rd = rd;
+                operands.imm = sign_extention_i32(imm, 20);
+                (Format::J, op, 0, 0, 0)
+            }
+            Instruction::U(imm, rd, op) => {
+                operands.rd = rd;
+                operands.imm = imm;
+                (Format::U, op, 0, 0, 0)
+            }
+            Instruction::R4(fs3, funct2, fs2, fs1, funct3, fd, op) => {
+                operands.rs3 = fs3;
+                operands.rs2 = fs2;
+                operands.rs1 = fs1;
+                operands.rd = fd;
+                operands.rm = funct3;
+                (Format::R4, op, funct3, funct2, fs2)
+            }
+            Instruction::Other => return None,
+        };
+        let def = self.find(format, op, funct3, funct7, rs2)?;
+        Some((def, operands))
+    }
+
+    /// Creates the instruction `inst`, which must not be `Instruction::Other` as
+    /// `decode_instruction` returns for words that are not instructions.
+    pub fn create_instruction_struct(&self, inst: Instruction) -> InstructionStruct {
+        match self.find_def(inst) {
+            Some((def, operands)) => InstructionStruct::new(def, operands),
+            None => {
+                panic!("unexpected instruction: {:?}", inst);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_is_unambiguous() {
+        for isa in [Isa::Cpuex2, Isa::Rv32imf] {
+            let instruction_set = InstructionSet::new(isa);
+            for def in INSTRUCTIONS.iter().chain(isa.get_instructions()) {
+                let funct3 = def.funct3.unwrap_or(0);
+                let funct7 = def.funct7.unwrap_or(0);
+                let rs2 = def.rs2.unwrap_or(0);
+                let found = instruction_set
+                    .find(def.format, def.op, funct3, funct7, rs2)
+                    .unwrap();
+                assert_eq!(found.name, def.name);
+                assert!(instruction_set.defs_by_op[def.op as usize]
+                    .iter()
+                    .all(|other| other.format == def.format));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rv32imf() {
+        let instruction_set = InstructionSet::new(Isa::Rv32imf);
+        // jalr jumps to rs1 + imm with the lowest bit cleared
+        let jalr = instruction_set.find(Format::I, 103, 0b000, 0, 0).unwrap();
+        let Semantics::JumpRegister(f) = jalr.semantics else {
+            panic!("jalr is not a jump");
+        };
+        assert_eq!(f(0x100, 5), 0x104);
+        // fence is decoded as a no-op
+        let fence = instruction_set.decode_instruction(0x0ff0000f);
+        let inst = instruction_set.create_instruction_struct(fence);
+        assert_eq!(format!("{:?}", inst), "fence");
+        assert!(matches!(get_def(&inst).semantics, Semantics::System(_)));
+        // the float branches and I/O of cpuex2 are not decoded
+        for op in [100, 116, 117] {
+            assert!(instruction_set.defs_by_op[op].is_empty());
+        }
+    }
+
+    #[test]
+    fn test_unknown_funct_is_not_an_instruction() {
+        for isa in [Isa::Cpuex2, Isa::Rv32imf] {
+            let instruction_set = InstructionSet::new(isa);
+            // op 51 with funct7 0b0100001, and op 3 with funct3 0b111
+            for inst in [0x42000033, 0x00007003] {
+                assert!(matches!(
+                    instruction_set.decode_instruction(inst),
+                    Instruction::Other
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_register() {
+        let mut instruction_set = InstructionSet::new(Isa::Cpuex2);
+        let sub = Semantics::IntR(&|a, b| a.wrapping_sub(b));
+        let rsub = InstructionDef::new("rsub", Format::R, 11, Some(0), Some(0), sub);
+        instruction_set.register(rsub).unwrap();
+        // custom-0 is not known to the decoder, so the format comes from the registry
+        let decoded = instruction_set.decode_instruction(2 << 20 | 1 << 15 | 3 << 7 | 11);
+        let inst = instruction_set.create_instruction_struct(decoded);
+        assert_eq!(format!("{:?}", inst), "rsub x3, x1, x2");
+        // registered instructions take precedence over the built-in ones
+        let add = InstructionDef::new("add2", Format::R, 51, Some(0), Some(0), sub);
+        instruction_set.register(add).unwrap();
+        let inst = instruction_set.create_instruction_struct(decode_instruction(51));
+        assert_eq!(get_name(&inst), "add2");
+        let lui = InstructionDef::new("lui2", Format::I, 55, None, None, sub);
+        assert!(instruction_set.register(lui).is_err());
+    }
+}
@@ -1,20 +1,49 @@
+mod bus;
 mod cache;
+mod compressed;
 mod core;
+mod csr;
+mod custom_instruction;
+mod data_loader;
 mod decoder;
 mod fpu_emulator;
 mod fpu_tester;
+mod heatmap;
 mod instruction;
 mod instruction_memory;
+mod isa;
 mod memory;
+mod memory_check;
+mod memory_dump;
+mod memory_trace;
+mod miss_classifier;
+mod mshr;
+mod prefetcher;
 mod register;
 mod sld_loader;
+mod stack_distance;
+mod stack_monitor;
+mod syscall;
 mod types;
+mod uninit_checker;
 mod utils;
+use crate::bus::*;
 use crate::core::*;
 use crate::instruction_memory::*;
 use clap::Parser;
+use custom_instruction::*;
+use data_loader::*;
+use fpu_emulator::FmaMode;
 use fpu_tester::*;
-use memory::MEMORY_SIZE;
+use heatmap::*;
+use isa::Isa;
+use memory::INITIAL_STACK_POINTER;
+use memory_check::*;
+use memory_dump::*;
+use memory_trace::*;
+use prefetcher::*;
+use stack_distance::*;
+use stack_monitor::*;
 use std::fs::File;
 use std::io::Read;
 use types::*;
@@ -48,6 +77,174 @@ struct Args {
     /// The output file name is the same as the input binary file name, but the extension is changed to ".dasm"
     #[arg(short, long)]
     disassemble: bool,
+
+    /// Name of the data file to load into memory before running (e.g. the .data file of cpuex2-assembler)
+    #[arg(long)]
+    data: Option<String>,
+
+    /// Address to load the data file at
+    #[arg(long, value_parser = parse_address, default_value = "0")]
+    data_base: Address,
+
+    /// Format of the data file
+    #[arg(long, value_enum, default_value_t = DataFormat::Bin)]
+    data_format: DataFormat,
+
+    /// Memory-mapped I/O mode
+    /// If this flag is set, loads and stores to the UART (0x80000000) and the cycle counter (0x80001000) go to the devices instead of memory
+    #[arg(long)]
+    mmio: bool,
+
+    /// Unified memory mode
+    /// If this flag is set, instructions and data share one address space: the program is loaded into data memory and fetched from it (seeing stores still in the data cache, but without counting cache accesses), so stores to code addresses are visible to fetch
+    #[arg(long)]
+    unified_memory: bool,
+
+    /// Latency of mul, mulh, mulhsu and mulhu in cycles
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..), default_value_t = 1)]
+    mul_latency: u64,
+
+    /// Latency of div, divu, rem and remu in cycles
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..), default_value_t = 1)]
+    div_latency: u64,
+
+    /// Instruction set of the binary
+    /// With rv32imf, binaries from the GNU RISC-V toolchain (objcopy -O binary) run unmodified; add --unified-memory if they read data placed next to the code
+    #[arg(long, value_enum, default_value_t = Isa::Cpuex2)]
+    isa: Isa,
+
+    /// Compressed instruction mode
+    /// If this flag is set, 16-bit instructions of the C extension are decoded as the 32-bit instructions they expand to and the binary may end with one; only for rv32imf, since the cpuex2 ops 100, 116 and 117 are 16-bit encodings in the C extension
+    #[arg(long)]
+    compressed: bool,
+
+    /// Code size stats mode
+    /// If this flag is set, the simulator will print the static and dynamic instruction counts and code sizes, with the sizes after compressing every instruction that has a compressed form
+    #[arg(long)]
+    code_size_stats: bool,
+
+    /// Clock frequency in MHz, used to convert cycles to time for the gettimeofday and clock_gettime system calls
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..), default_value_t = 100)]
+    clock_frequency: u64,
+
+    /// File of custom instructions, one `name kind op funct3 funct7 expression` per line
+    /// (e.g. `fbgt float_branch 100 0b110 * b < a`)
+    #[arg(long)]
+    custom_instructions: Option<String>,
+
+    /// Rounding of fmadd, fmsub, fnmsub and fnmadd.
+    /// Only for cpuex2; rv32imf always rounds once as IEEE 754 specifies
+    #[arg(long, value_enum, default_value_t = FmaMode::Unfused)]
+    fma_mode: FmaMode,
+
+    /// Check data accesses for out-of-range and misaligned addresses
+    #[arg(long, value_enum, default_value_t = MemoryCheck::Ignore)]
+    memory_check: MemoryCheck,
+
+    /// Additional address range that checked accesses may touch (START:END or START+LENGTH, can be repeated)
+    /// By default, accesses must lie in the stack/heap area below the initial stack pointer, the loaded data or the heap grown by brk; memory-mapped devices are always accessible
+    #[arg(long, value_parser = parse_address_range)]
+    mapped_range: Vec<(Address, Address)>,
+
+    /// Address ranges of memory to dump (START:END or START+LENGTH, can be repeated)
+    /// The dump reads through the data cache, so it reflects dirty lines
+    #[arg(long, value_parser = parse_address_range)]
+    dump: Vec<(Address, Address)>,
+
+    /// When to dump memory: end (end of program), inst:N (when the instruction count reaches N) or pc:ADDRESS (when the instruction at ADDRESS is executed first)
+    /// If an inst or pc trigger is never reached, memory is dumped at the end of program
+    #[arg(long, value_parser = parse_dump_trigger, default_value = "end")]
+    dump_at: DumpTrigger,
+
+    /// Format of the memory dump
+    #[arg(long, value_enum, default_value_t = DumpFormat::Hex)]
+    dump_format: DumpFormat,
+
+    /// Name of the memory dump file
+    /// The default is the same as the input binary file name, but the extension is changed to ".dump"
+    #[arg(long)]
+    dump_file: Option<String>,
+
+    /// Classify data cache misses into compulsory, capacity and conflict misses per instruction
+    /// The value is the number of the most missing instructions to show
+    #[arg(long)]
+    miss_stats: Option<usize>,
+
+    /// Name of the file to write the trace of every data memory access to
+    #[arg(long)]
+    trace: Option<String>,
+
+    /// Format of the memory access trace
+    #[arg(long, value_enum, default_value_t = TraceFormat::Dinero)]
+    trace_format: TraceFormat,
+
+    /// Address range to trace (START:END or START+LENGTH, decimal or 0x-prefixed hex)
+    /// If this flag is not set, all data memory accesses are traced
+    #[arg(long, value_parser = parse_address_range)]
+    trace_range: Option<(Address, Address)>,
+
+    /// Hardware prefetcher attached to the data cache
+    #[arg(long, value_enum)]
+    prefetcher: Option<PrefetcherKind>,
+
+    /// Number of lines the prefetcher fetches ahead on each trigger (for stream, the size of each stream buffer)
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..), default_value_t = 1)]
+    prefetch_degree: u64,
+
+    /// Monitor the stack pointer and the heap pointer, and warn or stop when they collide
+    #[arg(long, value_enum)]
+    stack_monitor: Option<StackMonitorMode>,
+
+    /// Index of the heap pointer register for the stack monitor
+    #[arg(long, value_parser = clap::value_parser!(u8).range(3..32), default_value_t = 3)]
+    heap_register: u8,
+
+    /// Name of the file to log the stack and heap pointers to whenever either reaches a new extreme
+    #[arg(long)]
+    stack_monitor_log: Option<String>,
+
+    /// Report loads from never-written memory words and reads of never-written registers (other than x0, ra and sp)
+    #[arg(long)]
+    uninit_check: bool,
+
+    /// Number of MSHRs of the non-blocking data cache timing model
+    /// If this flag is set, loads and stores proceed under outstanding misses and only dependent instructions stall
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    mshr: Option<u64>,
+
+    /// Miss penalty in cycles for the non-blocking data cache timing model
+    #[arg(long, default_value_t = 20)]
+    miss_penalty: u128,
+
+    /// Name of the file to write LRU reuse (stack) distance histograms to
+    /// If this flag is set, the simulator computes the hit rates of every cache geometry given by the --sd-* flags in one run
+    #[arg(long)]
+    stack_distance: Option<String>,
+
+    /// Line sizes for the stack distance analysis (comma separated powers of two)
+    #[arg(long, value_delimiter = ',', value_parser = parse_power_of_two, default_value = "16,32,64,128")]
+    sd_line_sizes: Vec<usize>,
+
+    /// Maximum number of sets for the stack distance analysis (set numbers from 1 to this, power of two)
+    #[arg(long, value_parser = parse_power_of_two, default_value = "4096")]
+    sd_max_sets: usize,
+
+    /// Maximum associativity for the stack distance analysis (power of two)
+    #[arg(long, value_parser = parse_power_of_two, default_value = "16")]
+    sd_max_ways: usize,
+
+    /// Name of the PPM file to write the memory access heatmap to (rows: address blocks, columns: time)
+    /// The cache miss heatmap and the row addresses are written next to it as .miss.ppm and .txt
+    #[arg(long)]
+    heatmap: Option<String>,
+
+    /// Address block size of a heatmap row in bytes (power of two)
+    #[arg(long, value_parser = parse_power_of_two, default_value = "4096")]
+    heatmap_block: usize,
+
+    /// Number of instructions per heatmap column
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..), default_value_t = 100000)]
+    heatmap_interval: u64,
 }
 
 fn main() {
@@ -58,7 +255,127 @@ fn main() {
     } else {
         let mut core = Core::new();
         core.set_int_register(RA, INSTRUCTION_MEMORY_SIZE as Int);
-        core.set_int_register(SP, MEMORY_SIZE as Int);
+        core.set_int_register(SP, INITIAL_STACK_POINTER as Int);
+        core.set_memory_check(args.memory_check);
+        for &(start, end) in &args.mapped_range {
+            core.add_mapped_range(start as u64, end as u64);
+        }
+        core.set_mul_div_latency(args.mul_latency as u128, args.div_latency as u128);
+        core.set_fma_mode(args.fma_mode);
+        core.set_isa(args.isa);
+        if args.compressed {
+            if args.isa != Isa::Rv32imf {
+                eprintln!(
+                    "Failed in enabling compressed instructions (only rv32imf supports them)."
+                );
+                std::process::exit(1);
+            }
+            core.enable_compressed_instructions();
+        }
+        if args.code_size_stats {
+            core.enable_code_size_stats();
+        }
+        core.set_clock_frequency(args.clock_frequency * 1_000_000);
+        if args.unified_memory {
+            core.enable_unified_memory();
+        }
+        if let Some(custom_instruction_file_path) = &args.custom_instructions {
+            let result = load_custom_instructions(custom_instruction_file_path).and_then(|defs| {
+                defs.into_iter()
+                    .try_for_each(|def| core.register_instruction(def))
+            });
+            if let Err(e) = result {
+                eprintln!("Failed in loading custom instructions ({}).", e);
+                std::process::exit(1);
+            }
+        }
+        if args.mmio {
+            let mut bus = Bus::new();
+            let result = bus
+                .attach(UART_ADDRESS, UART_SIZE, Box::new(Uart))
+                .and_then(|_| {
+                    bus.attach(
+                        CYCLE_COUNTER_ADDRESS,
+                        CYCLE_COUNTER_SIZE,
+                        Box::new(CycleCounter),
+                    )
+                });
+            if let Err(e) = result {
+                eprintln!("Failed in attaching device ({}).", e);
+                std::process::exit(1);
+            }
+            core.set_bus(bus);
+        }
+        if !args.dump.is_empty() {
+            let dump_file_path = match &args.dump_file {
+                Some(path) => path.clone(),
+                None => {
+                    let input = args.bin.as_ref().unwrap();
+                    if input.ends_with(".bin") {
+                        input.replace(".bin", ".dump")
+                    } else {
+                        format!("{}.dump", input)
+                    }
+                }
+            };
+            let memory_dumper = MemoryDumper::new(
+                args.dump.clone(),
+                args.dump_at,
+                args.dump_format,
+                &dump_file_path,
+            );
+            core.set_memory_dumper(memory_dumper);
+        }
+        if let Some(top_num) = args.miss_stats {
+            core.enable_miss_classification(top_num);
+        }
+        if let Some(kind) = args.prefetcher {
+            core.set_prefetcher(kind, args.prefetch_degree as usize);
+        }
+        if let Some(mode) = args.stack_monitor {
+            let result = core.set_stack_monitor(
+                mode,
+                args.heap_register as usize,
+                args.stack_monitor_log.as_deref(),
+            );
+            if let Err(e) = result {
+                eprintln!("Failed in creating stack monitor log file ({}).", e);
+                std::process::exit(1);
+            }
+        }
+        if args.uninit_check {
+            core.enable_uninit_check();
+        }
+        if let Some(mshr_num) = args.mshr {
+            if let Err(e) = core.set_mshr_timing_model(mshr_num as usize, args.miss_penalty) {
+                eprintln!("Failed in creating MSHR timing model ({}).", e);
+                std::process::exit(1);
+            }
+        }
+        if let Some(stack_distance_file_path) = &args.stack_distance {
+            core.set_stack_distance_analyzer(
+                StackDistanceAnalyzer::new(&args.sd_line_sizes, args.sd_max_sets, args.sd_max_ways),
+                stack_distance_file_path,
+            );
+        }
+        if let Some(heatmap_file_path) = &args.heatmap {
+            core.set_heatmap(
+                Heatmap::new(
+                    args.heatmap_block,
+                    args.heatmap_interval as InstructionCount,
+                ),
+                heatmap_file_path,
+            );
+        }
+        if let Some(trace_file_path) = &args.trace {
+            match MemoryTracer::new(trace_file_path, args.trace_format, args.trace_range) {
+                Ok(memory_tracer) => core.set_memory_tracer(memory_tracer),
+                Err(e) => {
+                    eprintln!("Failed in creating trace file ({}).", e);
+                    std::process::exit(1);
+                }
+            }
+        }
         let input = args.bin.unwrap();
         match File::open(input.clone()) {
             Err(e) => {
@@ -77,27 +394,39 @@ fn main() {
                         inst = 0;
                     }
                 }
-                if inst_count % 4 != 0 {
+                if args.compressed && inst_count % 4 == 2 {
+                    // a compressed instruction in the lower half of the last word
+                    core.store_instruction(inst_count - 2, inst);
+                } else if inst_count % 4 != 0 {
                     panic!("Reading file failed.\nThe size of sum of instructions is not a multiple of 4. {}", inst_count);
                 }
+                if let Some(data_file_path) = &args.data {
+                    let result = load_data_file(data_file_path, args.data_format)
+                        .and_then(|words| core.load_data(args.data_base, &words));
+                    if let Err(e) = result {
+                        eprintln!("Failed in loading data file ({}).", e);
+                        std::process::exit(1);
+                    }
+                }
                 if args.disassemble {
                     let disassemble_file_path = &input.replace(".bin", ".dasm");
-                    disassemble(&buf, disassemble_file_path);
+                    disassemble(&buf, disassemble_file_path, core.get_instruction_set());
                 }
                 let verbose = args.verbose.unwrap_or(0);
                 let interval = 0;
-                // let data_file_path = &input.replace(".bin", ".data");
                 let ppm_file_path = &input.replace(".bin", ".ppm");
                 let sld_file_path = &args.sld.unwrap();
                 let pc_file_path = &input.replace(".bin", ".pc");
                 core.run(
                     verbose,
                     interval,
-                    // data_file_path,
                     ppm_file_path,
                     sld_file_path,
                     pc_file_path,
                 );
+                if let Some(code) = core.get_exit_code() {
+                    std::process::exit(code);
+                }
             }
         }
     }
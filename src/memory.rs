@@ -1,60 +1,132 @@
 use crate::cache::LINE_SIZE;
 use crate::types::*;
 use crate::utils::*;
-pub const MEMORY_SIZE: usize = 128 * 1024 * 1024;
+/// The whole 32-bit address space, which does not fit in `usize` on 32-bit hosts.
+pub const MEMORY_SIZE: u64 = 1 << 32;
 pub const WORD_SIZE: usize = 4;
+pub const PAGE_SIZE: usize = 4 * 1024;
+/// Initial stack pointer, the top of the former 128 MiB memory so the layout of existing programs is unchanged.
+pub const INITIAL_STACK_POINTER: Address = 128 * 1024 * 1024;
+const PAGE_WORD_NUM: usize = PAGE_SIZE / WORD_SIZE;
+const PAGE_TABLE_SIZE: usize = 1024;
+const PAGE_DIRECTORY_SIZE: usize = (MEMORY_SIZE / (PAGE_SIZE * PAGE_TABLE_SIZE) as u64) as usize;
 
+type Page = [MemoryValue; PAGE_WORD_NUM];
+type PageTable = [Option<Box<Page>>; PAGE_TABLE_SIZE];
+
+/// Sparse memory backed by a two-level page table (10 + 10 bits of page number).
+/// A page is allocated on the first store to it, and loads from unallocated pages read 0.
 pub struct Memory {
-    values: [MemoryValue; MEMORY_SIZE / WORD_SIZE],
+    directory: Vec<Option<Box<PageTable>>>,
+    touched_page_count: usize,
 }
 
 impl Memory {
     pub fn new() -> Self {
-        let init_val = 0;
-        let values = [init_val; MEMORY_SIZE / WORD_SIZE];
-        Memory { values }
+        let directory = (0..PAGE_DIRECTORY_SIZE).map(|_| None).collect();
+        Memory {
+            directory,
+            touched_page_count: 0,
+        }
     }
 
-    // pub fn load_byte(&self, addr: Address) -> Byte {
-    //     u8_to_i8(self.values[addr as usize]) as Byte
-    // }
+    fn get_position(addr: Address) -> (usize, usize, usize) {
+        let page_number = addr as usize / PAGE_SIZE;
+        (
+            page_number / PAGE_TABLE_SIZE,
+            page_number % PAGE_TABLE_SIZE,
+            addr as usize % PAGE_SIZE / WORD_SIZE,
+        )
+    }
 
-    // pub fn load_ubyte(&self, addr: Address) -> UByte {
-    //     self.values[addr as usize] as UByte
-    // }
+    fn get_value(&self, addr: Address) -> MemoryValue {
+        let (directory_index, table_index, word_index) = Self::get_position(addr);
+        match &self.directory[directory_index] {
+            Some(table) => match &table[table_index] {
+                Some(page) => page[word_index],
+                None => 0,
+            },
+            None => 0,
+        }
+    }
 
-    // pub fn store_byte(&mut self, addr: Address, value: Byte) {
-    //     self.values[addr as usize] = i8_to_u8(value);
-    // }
+    fn get_value_mut(&mut self, addr: Address) -> &mut MemoryValue {
+        let (directory_index, table_index, word_index) = Self::get_position(addr);
+        let table = self.directory[directory_index]
+            .get_or_insert_with(|| Box::new(std::array::from_fn(|_| None)));
+        let page = table[table_index].get_or_insert_with(|| {
+            self.touched_page_count += 1;
+            Box::new([0; PAGE_WORD_NUM])
+        });
+        &mut page[word_index]
+    }
 
-    // fn store_ubyte(&mut self, addr: Address, value: UByte) {
-    //     self.values[addr as usize] = value;
-    // }
+    /// Returns the allocated pages merged into contiguous `[start, end)` ranges.
+    pub fn get_touched_ranges(&self) -> Vec<(u64, u64)> {
+        let mut ranges: Vec<(u64, u64)> = vec![];
+        for (directory_index, table) in self.directory.iter().enumerate() {
+            let Some(table) = table else {
+                continue;
+            };
+            for (table_index, page) in table.iter().enumerate() {
+                if page.is_none() {
+                    continue;
+                }
+                let start = ((directory_index * PAGE_TABLE_SIZE + table_index) * PAGE_SIZE) as u64;
+                let end = start + PAGE_SIZE as u64;
+                match ranges.last_mut() {
+                    Some(last) if last.1 == start => last.1 = end,
+                    _ => ranges.push((start, end)),
+                }
+            }
+        }
+        ranges
+    }
+
+    pub fn show_touched_pages(&self) {
+        println!("---------- touched pages ----------");
+        println!(
+            "touched page count: {} ({} KiB)",
+            self.touched_page_count,
+            self.touched_page_count * PAGE_SIZE / 1024
+        );
+        for (start, end) in self.get_touched_ranges() {
+            println!(
+                "[{:#010x}, {:#010x}): {} pages",
+                start,
+                end,
+                (end - start) / PAGE_SIZE as u64
+            );
+        }
+    }
 
-    // pub fn load_half(&self, addr: Address) -> Half {
-    //     let mut load_value: u16 = 0;
+    pub fn load_ubyte(&self, addr: Address) -> UByte {
+        (self.get_value(addr) >> ((addr & 3) * 8)) as UByte
+    }
 
-    //     for i in 0..2 {
-    //         load_value += (self.load_ubyte(addr + i) as u16) << (8 * i);
-    //     }
-    //     u16_to_i16(load_value) as Half
-    // }
+    pub fn store_byte(&mut self, addr: Address, value: Byte) {
+        let shift = (addr & 3) * 8;
+        let word = self.get_value_mut(addr);
+        *word = *word & !(0xff << shift) | (i8_to_u8(value) as MemoryValue) << shift;
+    }
 
-    // pub fn load_uhalf(&self, addr: Address) -> UHalf {
-    //     let mut load_value: u16 = 0;
+    /// Halfwords are accessed at `addr & !1`, like words at `addr & !3`.
+    pub fn load_uhalf(&self, addr: Address) -> UHalf {
+        (self.get_value(addr) >> ((addr & 2) * 8)) as UHalf
+    }
 
-    //     for i in 0..2 {
-    //         load_value += (self.load_ubyte(addr + i) as u16) << (8 * i);
-    //     }
-    //     load_value as UHalf
-    // }
+    pub fn store_half(&mut self, addr: Address, value: Half) {
+        let shift = (addr & 2) * 8;
+        let word = self.get_value_mut(addr);
+        *word = *word & !(0xffff << shift) | (i16_to_u16(value) as MemoryValue) << shift;
+    }
 
     pub fn load_word(&self, addr: Address) -> Word {
-        u32_to_i32(self.values[addr as usize >> 2])
+        u32_to_i32(self.get_value(addr))
     }
 
     pub fn store_word(&mut self, addr: Address, value: Word) {
-        self.values[addr as usize >> 2] = i32_to_u32(value);
+        *self.get_value_mut(addr) = i32_to_u32(value);
     }
 
     pub fn get_cache_line(&self, addr: Address) -> [MemoryValue; LINE_SIZE / WORD_SIZE] {
@@ -71,3 +143,38 @@ impl Memory {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pages_are_allocated_on_store() {
+        let mut memory = Memory::new();
+        assert_eq!(memory.load_word(0xffff_fffc), 0);
+        assert_eq!(memory.touched_page_count, 0);
+        memory.store_word(0xffff_fffc, -1);
+        memory.store_half(0x1002, -2);
+        memory.store_byte(0x1ffd, 3);
+        assert_eq!(memory.load_word(0xffff_fffc), -1);
+        assert_eq!(memory.load_word(0x1000), u32_to_i32(0xfffe_0000));
+        assert_eq!(memory.load_ubyte(0x1ffd), 3);
+        assert_eq!(memory.touched_page_count, 2);
+    }
+
+    #[test]
+    fn test_touched_ranges() {
+        let mut memory = Memory::new();
+        for addr in [0x3000, 0x1000, 0x2004, 0x40_0000, 0xffff_f000] {
+            memory.store_word(addr, 1);
+        }
+        assert_eq!(
+            memory.get_touched_ranges(),
+            vec![
+                (0x1000, 0x4000),
+                (0x40_0000, 0x40_1000),
+                (0xffff_f000, 0x1_0000_0000)
+            ]
+        );
+    }
+}
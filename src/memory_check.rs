@@ -0,0 +1,130 @@
+use std::fmt;
+
+use crate::memory::*;
+use crate::types::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum MemoryCheck {
+    /// Don't check data accesses
+    Ignore,
+    /// Report faulting accesses and perform them anyway (misaligned accesses drop the low
+    /// bits, and out-of-range accesses go to memory outside the mapped ranges)
+    Warn,
+    /// Report the first faulting access and stop the program
+    Trap,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryFault {
+    OutOfRange,
+    Misaligned,
+}
+
+impl fmt::Display for MemoryFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoryFault::OutOfRange => write!(f, "out-of-range"),
+            MemoryFault::Misaligned => write!(f, "misaligned"),
+        }
+    }
+}
+
+/// Returns the `[start, end)` ranges accesses are checked against by default: the
+/// stack/heap area below the initial stack pointer, which also holds a program loaded
+/// at address 0 in unified memory mode.
+pub fn default_mapped_ranges() -> Vec<(u64, u64)> {
+    vec![(0, INITIAL_STACK_POINTER as u64)]
+}
+
+/// Checks an access of `size` bytes, which must lie in one of `mapped_ranges`.
+/// The memory itself covers the whole address space, so the ranges bound wild pointers.
+pub fn check_access(
+    addr: Address,
+    size: usize,
+    mapped_ranges: &[(u64, u64)],
+) -> Option<MemoryFault> {
+    let (start, end) = (addr as u64, addr as u64 + size as u64);
+    if !mapped_ranges
+        .iter()
+        .any(|&(range_start, range_end)| range_start <= start && end <= range_end)
+    {
+        Some(MemoryFault::OutOfRange)
+    } else if !addr.is_multiple_of(size as Address) {
+        Some(MemoryFault::Misaligned)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::*;
+
+    #[test]
+    fn test_check_access() {
+        let ranges = default_mapped_ranges();
+        let check = |addr, size| check_access(addr, size, &ranges);
+        assert_eq!(check(0x1000, 4), None);
+        assert_eq!(check(0x1002, 2), None);
+        assert_eq!(check(0x1003, 1), None);
+        assert_eq!(check(0x1002, 4), Some(MemoryFault::Misaligned));
+        assert_eq!(check(0x1001, 2), Some(MemoryFault::Misaligned));
+        assert_eq!(check(0x7ff_fffc, 4), None);
+        assert_eq!(check(0x800_0000, 4), Some(MemoryFault::OutOfRange));
+        assert_eq!(check(0x9000_0000, 4), Some(MemoryFault::OutOfRange));
+        assert_eq!(check(0xffff_fffe, 4), Some(MemoryFault::OutOfRange));
+        assert_eq!(check(0xffff_ffff, 2), Some(MemoryFault::OutOfRange));
+
+        let ranges = [(0, 0x100), (0x9000_0000, 0x1_0000_0000)];
+        let check = |addr, size| check_access(addr, size, &ranges);
+        assert_eq!(check(0x9000_0000, 4), None);
+        assert_eq!(check(0xffff_fffc, 4), None);
+        assert_eq!(check(0xfc, 4), None);
+        assert_eq!(check(0x100, 4), Some(MemoryFault::OutOfRange));
+    }
+
+    #[test]
+    fn test_mapped_ranges() {
+        let mut core = Core::new();
+        core.set_memory_check(MemoryCheck::Trap);
+        core.load_data(0x9000_0000, &[1, 2]).unwrap();
+        assert_eq!(core.load_word(0x9000_0004), 2);
+        // past the loaded data
+        core.store_word(0x9000_0008, 3);
+        assert_eq!(core.peek_word(0x9000_0008), 0);
+
+        let mut core = Core::new();
+        core.set_memory_check(MemoryCheck::Trap);
+        core.add_mapped_range(0xa000_0000, 0xa000_1000);
+        core.store_word(0xa000_0ffc, 3);
+        assert_eq!(core.load_word(0xa000_0ffc), 3);
+    }
+
+    #[test]
+    fn test_faulting_accesses() {
+        let mut core = Core::new();
+        core.set_memory_check(MemoryCheck::Warn);
+        core.store_word(0x1000, 0x11223344);
+        // a misaligned access drops the low bits
+        assert_eq!(core.load_word(0x1002), 0x11223344);
+        core.store_word(0x1006, 0x55667788);
+        assert_eq!(core.peek_word(0x1004), 0x55667788);
+        // out-of-range accesses are performed, aligned or not
+        core.store_word(0x9000_0000, 0x99);
+        assert_eq!(core.load_word(0x9000_0000), 0x99);
+        core.store_word(0x7ff_fffc, 0x77);
+        assert_eq!(core.load_word(0x7ff_fffe), 0x77);
+        core.store_word(0x7ff_fffe, 0x88);
+        assert_eq!(core.peek_word(0x7ff_fffc), 0x88);
+
+        // a trapping access is not performed
+        core.set_memory_check(MemoryCheck::Trap);
+        core.store_word(0x1002, 0);
+        assert_eq!(core.peek_word(0x1000), 0x11223344);
+        assert_eq!(core.load_word(0x1002), 0);
+        core.store_word(0x9000_0000, 0);
+        assert_eq!(core.peek_word(0x9000_0000), 0x99);
+        assert_eq!(core.load_word(0x9000_0000), 0);
+    }
+}
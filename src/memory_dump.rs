@@ -0,0 +1,164 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use crate::types::*;
+use crate::utils::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum DumpFormat {
+    /// `address value` in hexadecimal, one word per line
+    Hex,
+    /// Raw little-endian words of the ranges, concatenated
+    Bin,
+    /// `address value float` with the word also interpreted as a single-precision float
+    Float,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DumpTrigger {
+    End,
+    InstructionCount(InstructionCount),
+    Pc(Address),
+}
+
+/// Parses `end`, `inst:N` or `pc:ADDRESS`.
+pub fn parse_dump_trigger(s: &str) -> Result<DumpTrigger, String> {
+    if s == "end" {
+        Ok(DumpTrigger::End)
+    } else if let Some(count) = s.strip_prefix("inst:") {
+        count
+            .parse::<InstructionCount>()
+            .map(DumpTrigger::InstructionCount)
+            .map_err(|e| format!("invalid instruction count '{}' ({})", count, e))
+    } else if let Some(pc) = s.strip_prefix("pc:") {
+        parse_address(pc).map(DumpTrigger::Pc)
+    } else {
+        Err(format!(
+            "invalid dump trigger '{}' (expected end, inst:N or pc:ADDRESS)",
+            s
+        ))
+    }
+}
+
+pub struct MemoryDumper {
+    ranges: Vec<(Address, Address)>,
+    trigger: DumpTrigger,
+    format: DumpFormat,
+    path: String,
+    dumped: bool,
+}
+
+impl MemoryDumper {
+    pub fn new(
+        ranges: Vec<(Address, Address)>,
+        trigger: DumpTrigger,
+        format: DumpFormat,
+        path: &str,
+    ) -> Self {
+        MemoryDumper {
+            ranges,
+            trigger,
+            format,
+            path: path.to_string(),
+            dumped: false,
+        }
+    }
+
+    /// Returns true if the dump is due before executing the instruction at `pc`.
+    pub fn is_triggered(&self, pc: Option<Address>, inst_count: InstructionCount) -> bool {
+        if self.dumped {
+            return false;
+        }
+        match self.trigger {
+            DumpTrigger::End => false,
+            DumpTrigger::InstructionCount(count) => inst_count >= count,
+            DumpTrigger::Pc(trigger_pc) => pc == Some(trigger_pc),
+        }
+    }
+
+    pub fn is_dumped(&self) -> bool {
+        self.dumped
+    }
+
+    /// Writes the ranges, reading each word with `load`.
+    pub fn dump(&mut self, mut load: impl FnMut(Address) -> Word) -> io::Result<()> {
+        self.dumped = true;
+        let mut writer = BufWriter::new(File::create(&self.path)?);
+        for &(start, end) in &self.ranges {
+            let mut addr = start & !3;
+            while addr < end {
+                let value = i32_to_u32(load(addr));
+                match self.format {
+                    DumpFormat::Hex => writeln!(writer, "{:08x} {:08x}", addr, value)?,
+                    DumpFormat::Bin => writer.write_all(&value.to_le_bytes())?,
+                    DumpFormat::Float => writeln!(
+                        writer,
+                        "{:08x} {:08x} {:?}",
+                        addr,
+                        value,
+                        f32::from_bits(value)
+                    )?,
+                }
+                match addr.checked_add(4) {
+                    Some(next_addr) => addr = next_addr,
+                    None => break,
+                }
+            }
+        }
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dump_trigger() {
+        assert_eq!(parse_dump_trigger("end"), Ok(DumpTrigger::End));
+        assert_eq!(
+            parse_dump_trigger("inst:100"),
+            Ok(DumpTrigger::InstructionCount(100))
+        );
+        assert_eq!(parse_dump_trigger("pc:0x40"), Ok(DumpTrigger::Pc(0x40)));
+        assert!(parse_dump_trigger("inst:-1").is_err());
+        assert!(parse_dump_trigger("pc:x").is_err());
+        assert!(parse_dump_trigger("start").is_err());
+    }
+
+    #[test]
+    fn test_trigger() {
+        let path = std::env::temp_dir().join(format!("test_trigger-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut dumper = MemoryDumper::new(
+            vec![(0x100, 0x108)],
+            DumpTrigger::Pc(0x40),
+            DumpFormat::Hex,
+            path,
+        );
+        assert!(!dumper.is_triggered(None, 10));
+        assert!(!dumper.is_triggered(Some(0x44), 10));
+        assert!(dumper.is_triggered(Some(0x40), 10));
+        dumper.dump(|addr| addr as Word * 2).unwrap();
+        // a dump is written only once
+        assert!(dumper.is_dumped());
+        assert!(!dumper.is_triggered(Some(0x40), 11));
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "00000100 00000200\n00000104 00000208\n"
+        );
+        std::fs::remove_file(path).unwrap();
+
+        let dumper = MemoryDumper::new(
+            vec![],
+            DumpTrigger::InstructionCount(5),
+            DumpFormat::Hex,
+            path,
+        );
+        assert!(!dumper.is_triggered(Some(0), 4));
+        assert!(dumper.is_triggered(Some(0), 5));
+        assert!(dumper.is_triggered(None, 6));
+        let dumper = MemoryDumper::new(vec![], DumpTrigger::End, DumpFormat::Hex, path);
+        assert!(!dumper.is_triggered(Some(0), u128::MAX));
+    }
+}
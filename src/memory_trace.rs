@@ -0,0 +1,139 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use crate::types::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum TraceFormat {
+    /// Dinero "din" text: `label address pc inst_count hit`, one access per line
+    /// (label 0 is a read and 1 is a write; Dinero ignores the columns after the address)
+    Dinero,
+    /// 17-byte little-endian records: address (u32), pc (u32), inst_count (u64), flags (u8)
+    /// (bit 0 of flags is set for a write and bit 1 for a cache hit)
+    Binary,
+}
+
+pub struct MemoryTracer {
+    /// `None` after a write has failed.
+    writer: Option<BufWriter<File>>,
+    format: TraceFormat,
+    address_range: Option<(Address, Address)>,
+}
+
+impl MemoryTracer {
+    pub fn new(
+        path: &str,
+        format: TraceFormat,
+        address_range: Option<(Address, Address)>,
+    ) -> io::Result<Self> {
+        let writer = Some(BufWriter::new(File::create(path)?));
+        Ok(MemoryTracer {
+            writer,
+            format,
+            address_range,
+        })
+    }
+
+    pub fn record(
+        &mut self,
+        addr: Address,
+        is_write: bool,
+        pc: Address,
+        inst_count: InstructionCount,
+        hit: bool,
+    ) {
+        if let Some((start, end)) = self.address_range {
+            if addr < start || addr >= end {
+                return;
+            }
+        }
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        let result = match self.format {
+            TraceFormat::Dinero => writeln!(
+                writer,
+                "{} {:08x} {:08x} {} {}",
+                is_write as u8,
+                addr,
+                pc,
+                inst_count,
+                if hit { "h" } else { "m" }
+            ),
+            TraceFormat::Binary => {
+                let mut record = [0; 17];
+                record[0..4].copy_from_slice(&addr.to_le_bytes());
+                record[4..8].copy_from_slice(&pc.to_le_bytes());
+                record[8..16].copy_from_slice(&(inst_count as u64).to_le_bytes());
+                record[16] = is_write as u8 | (hit as u8) << 1;
+                writer.write_all(&record)
+            }
+        };
+        if let Err(e) = result {
+            eprintln!(
+                "\nFailed in writing memory trace ({}). Tracing is stopped.",
+                e
+            );
+            self.writer = None;
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        match &mut self.writer {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record_trace(name: &str, format: TraceFormat) -> Vec<u8> {
+        let path = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut tracer = MemoryTracer::new(path, format, Some((0x100, 0x200))).unwrap();
+        tracer.record(0x100, false, 0x40, 7, true);
+        tracer.record(0x200, false, 0x44, 8, true);
+        tracer.record(0x1fc, true, 0x48, 0x1_0000_0001, false);
+        tracer.flush().unwrap();
+        let trace = std::fs::read(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        trace
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_write_error_stops_tracing() {
+        let mut tracer = MemoryTracer::new("/dev/full", TraceFormat::Binary, None).unwrap();
+        for i in 0..1000 {
+            tracer.record(i * 4, false, 0, 0, true);
+        }
+        assert!(tracer.writer.is_none());
+        assert!(tracer.flush().is_ok());
+    }
+
+    #[test]
+    fn test_dinero_format() {
+        let trace = record_trace("test_dinero_format", TraceFormat::Dinero);
+        assert_eq!(
+            String::from_utf8(trace).unwrap(),
+            "0 00000100 00000040 7 h\n1 000001fc 00000048 4294967297 m\n"
+        );
+    }
+
+    #[test]
+    fn test_binary_format() {
+        let trace = record_trace("test_binary_format", TraceFormat::Binary);
+        assert_eq!(trace.len(), 34);
+        assert_eq!(
+            &trace[..17],
+            &[0, 1, 0, 0, 0x40, 0, 0, 0, 7, 0, 0, 0, 0, 0, 0, 0, 2]
+        );
+        assert_eq!(
+            &trace[17..],
+            &[0xfc, 1, 0, 0, 0x48, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1]
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::types::*;
+use linked_hash_map::LinkedHashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MissKind {
+    Compulsory,
+    Capacity,
+    Conflict,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct PcMissStats {
+    pub access_count: usize,
+    pub compulsory_miss_count: usize,
+    pub capacity_miss_count: usize,
+    pub conflict_miss_count: usize,
+}
+
+impl PcMissStats {
+    pub fn get_miss_count(&self) -> usize {
+        self.compulsory_miss_count + self.capacity_miss_count + self.conflict_miss_count
+    }
+
+    fn add_miss(&mut self, kind: MissKind) {
+        match kind {
+            MissKind::Compulsory => self.compulsory_miss_count += 1,
+            MissKind::Capacity => self.capacity_miss_count += 1,
+            MissKind::Conflict => self.conflict_miss_count += 1,
+        }
+    }
+}
+
+/// Classifies data cache misses into the 3C model by shadowing the real cache
+/// with an infinite cache and a fully-associative LRU cache of the same size.
+pub struct MissClassifier {
+    offset_bit_num: usize,
+    line_num: usize,
+    seen_lines: HashSet<Address>,
+    fully_associative_lines: LinkedHashMap<Address, ()>,
+    pc_stats: HashMap<Address, PcMissStats>,
+    total: PcMissStats,
+}
+
+impl MissClassifier {
+    pub fn new(line_size: usize, line_num: usize) -> Self {
+        MissClassifier {
+            offset_bit_num: (line_size as u32).trailing_zeros() as usize,
+            line_num,
+            seen_lines: HashSet::new(),
+            fully_associative_lines: LinkedHashMap::with_capacity(line_num + 1),
+            pc_stats: HashMap::new(),
+            total: PcMissStats::default(),
+        }
+    }
+
+    /// Updates the shadow caches with an access and, if the real cache missed,
+    /// returns the class of the miss.
+    pub fn record(&mut self, pc: Address, addr: Address, hit: bool) -> Option<MissKind> {
+        let line_addr = addr >> self.offset_bit_num;
+        let first_access = self.seen_lines.insert(line_addr);
+        let fully_associative_hit = self
+            .fully_associative_lines
+            .get_refresh(&line_addr)
+            .is_some();
+        if !fully_associative_hit {
+            self.fully_associative_lines.insert(line_addr, ());
+            if self.fully_associative_lines.len() > self.line_num {
+                self.fully_associative_lines.pop_front();
+            }
+        }
+
+        let kind = if hit {
+            None
+        } else if first_access {
+            Some(MissKind::Compulsory)
+        } else if !fully_associative_hit {
+            Some(MissKind::Capacity)
+        } else {
+            Some(MissKind::Conflict)
+        };
+
+        let pc_stat = self.pc_stats.entry(pc).or_default();
+        pc_stat.access_count += 1;
+        self.total.access_count += 1;
+        if let Some(kind) = kind {
+            pc_stat.add_miss(kind);
+            self.total.add_miss(kind);
+        }
+        kind
+    }
+
+    pub fn get_total(&self) -> &PcMissStats {
+        &self.total
+    }
+
+    /// Returns the instructions with the most misses, most missing first.
+    pub fn get_top_missing_pcs(&self, n: usize) -> Vec<(Address, PcMissStats)> {
+        let mut pc_stats: Vec<(Address, PcMissStats)> = self
+            .pc_stats
+            .iter()
+            .filter(|(_, stats)| stats.get_miss_count() > 0)
+            .map(|(pc, stats)| (*pc, *stats))
+            .collect();
+        pc_stats.sort_by(|a, b| {
+            b.1.get_miss_count()
+                .cmp(&a.1.get_miss_count())
+                .then(a.0.cmp(&b.0))
+        });
+        pc_stats.truncate(n);
+        pc_stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compulsory_miss() {
+        let mut classifier = MissClassifier::new(64, 4);
+        assert_eq!(classifier.record(0, 0, false), Some(MissKind::Compulsory));
+        assert_eq!(classifier.record(0, 4, true), None);
+        assert_eq!(classifier.record(4, 64, false), Some(MissKind::Compulsory));
+        assert_eq!(classifier.get_total().compulsory_miss_count, 2);
+        assert_eq!(classifier.get_total().access_count, 3);
+    }
+
+    #[test]
+    fn test_capacity_miss() {
+        let mut classifier = MissClassifier::new(64, 2);
+        for line in 0..3 {
+            classifier.record(0, line * 64, false);
+        }
+        // line 0 was pushed out of the fully-associative shadow by lines 1 and 2
+        assert_eq!(classifier.record(0, 0, false), Some(MissKind::Capacity));
+    }
+
+    #[test]
+    fn test_conflict_miss() {
+        let mut classifier = MissClassifier::new(64, 4);
+        classifier.record(0, 0, false);
+        classifier.record(0, 64, false);
+        // still resident in the fully-associative shadow, so the real miss is a conflict
+        assert_eq!(classifier.record(8, 0, false), Some(MissKind::Conflict));
+        let top = classifier.get_top_missing_pcs(1);
+        assert_eq!(top[0].0, 0);
+        assert_eq!(top[0].1.get_miss_count(), 2);
+    }
+}
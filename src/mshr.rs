@@ -0,0 +1,223 @@
+use crate::types::*;
+
+#[derive(Clone, Copy)]
+struct Mshr {
+    line_addr: Address,
+    ready_cycle: Cycle,
+}
+
+#[derive(Default)]
+pub struct MshrStats {
+    pub miss_count: usize,
+    pub secondary_miss_count: usize,
+    pub access_under_miss_count: usize,
+    pub dependency_stall_cycles: Cycle,
+    pub mshr_full_stall_cycles: Cycle,
+}
+
+/// Timing model of a non-blocking data cache with miss status holding registers.
+/// A miss occupies an MSHR for `miss_penalty` cycles and only makes its destination register
+/// late; the pipeline stalls when an instruction sources a late register or all MSHRs are busy.
+/// Time is the pipeline cycle count plus the stall cycles inserted by this model.
+pub struct MshrTimingModel {
+    mshr_num: usize,
+    miss_penalty: Cycle,
+    mshrs: Vec<Mshr>,
+    int_ready_cycles: [Cycle; 32],
+    float_ready_cycles: [Cycle; 32],
+    last_cycle: Cycle,
+    occupancy_cycles: Vec<Cycle>,
+    stats: MshrStats,
+}
+
+impl MshrTimingModel {
+    pub fn new(mshr_num: usize, miss_penalty: Cycle) -> Result<Self, String> {
+        if mshr_num == 0 {
+            return Err("number of MSHRs must be positive".to_string());
+        }
+        Ok(MshrTimingModel {
+            mshr_num,
+            miss_penalty,
+            mshrs: Vec::with_capacity(mshr_num),
+            int_ready_cycles: [0; 32],
+            float_ready_cycles: [0; 32],
+            last_cycle: 0,
+            occupancy_cycles: vec![0; mshr_num + 1],
+            stats: MshrStats::default(),
+        })
+    }
+
+    pub fn get_mshr_num(&self) -> usize {
+        self.mshr_num
+    }
+
+    pub fn get_miss_penalty(&self) -> Cycle {
+        self.miss_penalty
+    }
+
+    pub fn get_stats(&self) -> &MshrStats {
+        &self.stats
+    }
+
+    /// `occupancy_cycles[n]` is the number of cycles during which `n` MSHRs were busy.
+    pub fn get_occupancy_cycles(&self) -> &[Cycle] {
+        &self.occupancy_cycles
+    }
+
+    pub fn get_stall_cycles(&self) -> Cycle {
+        self.stats.dependency_stall_cycles + self.stats.mshr_full_stall_cycles
+    }
+
+    /// Stall cycles of a blocking cache, which stalls the whole pipeline on every miss.
+    pub fn get_blocking_stall_cycles(&self) -> Cycle {
+        self.stats.miss_count as Cycle * self.miss_penalty
+    }
+
+    fn get_now(&self, pipeline_cycle: Cycle) -> Cycle {
+        pipeline_cycle + self.get_stall_cycles()
+    }
+
+    /// Advances the occupancy bookkeeping to `now`, releasing the MSHRs whose fills completed.
+    fn advance(&mut self, now: Cycle) {
+        while self.last_cycle < now {
+            let next_cycle = self
+                .mshrs
+                .iter()
+                .map(|mshr| mshr.ready_cycle)
+                .min()
+                .unwrap_or(now)
+                .min(now);
+            self.occupancy_cycles[self.mshrs.len()] += next_cycle - self.last_cycle;
+            self.last_cycle = next_cycle;
+            self.mshrs.retain(|mshr| mshr.ready_cycle > next_cycle);
+        }
+    }
+
+    fn get_ready_cycle_mut(&mut self, register: &RegisterId) -> Option<&mut Cycle> {
+        match *register {
+            RegisterId::Int(0) => None,
+            RegisterId::Int(rd) => Some(&mut self.int_ready_cycles[rd as usize]),
+            RegisterId::Float(fd) => Some(&mut self.float_ready_cycles[fd as usize]),
+        }
+    }
+
+    /// Records a data access of the instruction in the memory stage and returns the cycle
+    /// at which its data is available.
+    fn access(&mut self, pipeline_cycle: Cycle, line_addr: Address, hit: bool) -> Cycle {
+        let mut now = self.get_now(pipeline_cycle);
+        self.advance(now);
+        if !self.mshrs.is_empty() {
+            self.stats.access_under_miss_count += 1;
+        }
+        if hit {
+            return match self.mshrs.iter().find(|mshr| mshr.line_addr == line_addr) {
+                Some(mshr) => {
+                    self.stats.secondary_miss_count += 1;
+                    mshr.ready_cycle
+                }
+                None => now,
+            };
+        }
+        self.stats.miss_count += 1;
+        if self.mshrs.len() >= self.mshr_num {
+            let free_cycle = self
+                .mshrs
+                .iter()
+                .map(|mshr| mshr.ready_cycle)
+                .min()
+                .unwrap();
+            self.stats.mshr_full_stall_cycles += free_cycle - now;
+            now = free_cycle;
+            self.advance(now);
+        }
+        let ready_cycle = now + self.miss_penalty;
+        self.mshrs.push(Mshr {
+            line_addr,
+            ready_cycle,
+        });
+        ready_cycle
+    }
+
+    pub fn on_load(
+        &mut self,
+        pipeline_cycle: Cycle,
+        line_addr: Address,
+        hit: bool,
+        rd: Option<RegisterId>,
+    ) {
+        let ready_cycle = self.access(pipeline_cycle, line_addr, hit);
+        if let Some(ready) = rd.as_ref().and_then(|rd| self.get_ready_cycle_mut(rd)) {
+            *ready = ready_cycle;
+        }
+    }
+
+    pub fn on_store(&mut self, pipeline_cycle: Cycle, line_addr: Address, hit: bool) {
+        self.access(pipeline_cycle, line_addr, hit);
+    }
+
+    /// Stalls an instruction entering the exec stage until its source registers are ready.
+    /// A non-load destination register is overwritten in the pipeline, so it becomes ready.
+    pub fn on_exec(
+        &mut self,
+        pipeline_cycle: Cycle,
+        source_registers: &[RegisterId],
+        destination_register: Option<RegisterId>,
+    ) {
+        let now = self.get_now(pipeline_cycle);
+        let mut ready_cycle = now;
+        for rs in source_registers {
+            if let Some(ready) = self.get_ready_cycle_mut(rs) {
+                ready_cycle = ready_cycle.max(*ready);
+            }
+        }
+        self.stats.dependency_stall_cycles += ready_cycle - now;
+        if let Some(ready) = destination_register
+            .as_ref()
+            .and_then(|rd| self.get_ready_cycle_mut(rd))
+        {
+            *ready = 0;
+        }
+    }
+
+    /// Waits for the outstanding fills at the end of the program.
+    pub fn finish(&mut self, pipeline_cycle: Cycle) {
+        let now = self.get_now(pipeline_cycle);
+        let last_ready_cycle = self.mshrs.iter().map(|mshr| mshr.ready_cycle).max();
+        self.advance(last_ready_cycle.unwrap_or(now).max(now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_independent_access_proceeds_under_miss() {
+        let mut model = MshrTimingModel::new(2, 10).unwrap();
+        model.on_load(1, 0, false, Some(RegisterId::Int(5)));
+        model.on_load(2, 64, true, Some(RegisterId::Int(6)));
+        model.on_exec(3, &[RegisterId::Int(6)], Some(RegisterId::Int(7)));
+        assert_eq!(model.get_stall_cycles(), 0);
+        assert_eq!(model.get_stats().access_under_miss_count, 1);
+        // the consumer of the missing load waits until the fill completes at cycle 11
+        model.on_exec(4, &[RegisterId::Int(5)], Some(RegisterId::Int(8)));
+        assert_eq!(model.get_stats().dependency_stall_cycles, 7);
+        assert_eq!(model.get_blocking_stall_cycles(), 10);
+    }
+
+    #[test]
+    fn test_mshr_full_stall() {
+        let mut model = MshrTimingModel::new(1, 10).unwrap();
+        model.on_store(1, 0, false);
+        model.on_store(2, 64, false);
+        assert_eq!(model.get_stats().mshr_full_stall_cycles, 9);
+        model.finish(3);
+        // 1 idle cycle, then the two fills occupy the single MSHR for 10 cycles each
+        assert_eq!(model.get_occupancy_cycles(), &[1, 20]);
+    }
+
+    #[test]
+    fn test_no_mshr() {
+        assert!(MshrTimingModel::new(0, 10).is_err());
+    }
+}
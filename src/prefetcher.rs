@@ -0,0 +1,328 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::types::*;
+
+const STRIDE_TABLE_SIZE: usize = 64;
+const STRIDE_CONFIDENCE_MAX: u8 = 3;
+const STRIDE_CONFIDENCE_THRESHOLD: u8 = 2;
+const STREAM_NUM: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum PrefetcherKind {
+    /// Prefetch the following lines on a miss or on the first hit to a prefetched line
+    NextLine,
+    /// Prefetch along the address stride of each load/store, indexed by its PC
+    Stride,
+    /// Detect ascending or descending sequences of missing lines and run ahead of them into
+    /// stream buffers outside the cache; a miss that hits a buffer promotes the line into the cache
+    Stream,
+}
+
+#[derive(Clone, Copy)]
+struct StrideEntry {
+    pc: Address,
+    last_addr: Address,
+    stride: i64,
+    confidence: u8,
+}
+
+struct Stream {
+    last_line: i64,
+    direction: Option<i64>,
+    /// Lines prefetched ahead of `last_line`, nearest first.
+    buffer: VecDeque<i64>,
+}
+
+#[derive(Default)]
+pub struct PrefetchStats {
+    pub issued_count: usize,
+    pub useful_count: usize,
+    pub demand_miss_count: usize,
+    pub polluting_miss_count: usize,
+}
+
+pub struct Prefetcher {
+    kind: PrefetcherKind,
+    degree: usize,
+    offset_bit_num: usize,
+    memory_size: u64,
+    stride_table: [Option<StrideEntry>; STRIDE_TABLE_SIZE],
+    streams: VecDeque<Stream>,
+    prefetched_lines: HashSet<Address>,
+    lines_evicted_by_prefetch: HashSet<Address>,
+    stats: PrefetchStats,
+}
+
+impl Prefetcher {
+    pub fn new(kind: PrefetcherKind, degree: usize, line_size: usize, memory_size: u64) -> Self {
+        Prefetcher {
+            kind,
+            degree,
+            offset_bit_num: (line_size as u32).trailing_zeros() as usize,
+            memory_size,
+            stride_table: [None; STRIDE_TABLE_SIZE],
+            streams: VecDeque::with_capacity(STREAM_NUM),
+            prefetched_lines: HashSet::new(),
+            lines_evicted_by_prefetch: HashSet::new(),
+            stats: PrefetchStats::default(),
+        }
+    }
+
+    pub fn get_kind(&self) -> PrefetcherKind {
+        self.kind
+    }
+
+    pub fn get_stats(&self) -> &PrefetchStats {
+        &self.stats
+    }
+
+    fn get_line_addr(&self, addr: Address) -> Address {
+        addr >> self.offset_bit_num << self.offset_bit_num
+    }
+
+    /// Converts a line number to its address, dropping lines outside of memory.
+    fn line_to_addr(&self, line: i64) -> Option<Address> {
+        let addr = line << self.offset_bit_num;
+        if addr < 0 || addr as u64 + (1 << self.offset_bit_num) > self.memory_size {
+            None
+        } else {
+            Some(addr as Address)
+        }
+    }
+
+    /// Updates the statistics and the prefetcher state with a demand access,
+    /// and returns the addresses of the lines to prefetch into the cache.
+    /// The stream prefetcher keeps its lines in stream buffers and returns none.
+    pub fn on_demand_access(&mut self, pc: Address, addr: Address, hit: bool) -> Vec<Address> {
+        let line_addr = self.get_line_addr(addr);
+        let line = (line_addr >> self.offset_bit_num) as i64;
+        if self.kind == PrefetcherKind::Stream {
+            if !hit {
+                self.on_stream_miss(line);
+            }
+            return vec![];
+        }
+        let prefetched_line_hit = self.prefetched_lines.remove(&line_addr) && hit;
+        if prefetched_line_hit {
+            self.stats.useful_count += 1;
+        }
+        if !hit {
+            self.stats.demand_miss_count += 1;
+            if self.lines_evicted_by_prefetch.remove(&line_addr) {
+                self.stats.polluting_miss_count += 1;
+            }
+        }
+        let triggered = !hit || prefetched_line_hit;
+        let lines = match self.kind {
+            PrefetcherKind::NextLine => {
+                if triggered {
+                    (1..=self.degree as i64).map(|i| line + i).collect()
+                } else {
+                    vec![]
+                }
+            }
+            PrefetcherKind::Stride => self.train_stride(pc, addr),
+            PrefetcherKind::Stream => unreachable!("stream buffers are not in the cache"),
+        };
+        let mut line_addrs = vec![];
+        for line in lines {
+            if let Some(addr) = self.line_to_addr(line) {
+                if !line_addrs.contains(&addr) && addr != line_addr {
+                    line_addrs.push(addr);
+                }
+            }
+        }
+        line_addrs
+    }
+
+    fn train_stride(&mut self, pc: Address, addr: Address) -> Vec<i64> {
+        let index = (pc >> 2) as usize % STRIDE_TABLE_SIZE;
+        let entry = match &mut self.stride_table[index] {
+            Some(entry) if entry.pc == pc => entry,
+            entry => {
+                *entry = Some(StrideEntry {
+                    pc,
+                    last_addr: addr,
+                    stride: 0,
+                    confidence: 0,
+                });
+                return vec![];
+            }
+        };
+        let stride = addr as i64 - entry.last_addr as i64;
+        entry.last_addr = addr;
+        if stride == entry.stride {
+            entry.confidence = (entry.confidence + 1).min(STRIDE_CONFIDENCE_MAX);
+        } else if entry.confidence > 0 {
+            entry.confidence -= 1;
+        } else {
+            entry.stride = stride;
+        }
+        if entry.confidence < STRIDE_CONFIDENCE_THRESHOLD || entry.stride == 0 {
+            return vec![];
+        }
+        (1..=self.degree as i64)
+            .map(|i| (addr as i64 + entry.stride * i) >> self.offset_bit_num)
+            .collect()
+    }
+
+    /// Promotes a missing line from the stream buffer holding it, dropping the lines before
+    /// it, or otherwise trains the streams with the miss.
+    fn on_stream_miss(&mut self, line: i64) {
+        let found = self.streams.iter().enumerate().find_map(|(index, stream)| {
+            let position = stream
+                .buffer
+                .iter()
+                .position(|&buffered| buffered == line)?;
+            Some((index, position))
+        });
+        let mut stream = match found {
+            Some((index, position)) => {
+                self.stats.useful_count += 1;
+                let mut stream = self.streams.remove(index).unwrap();
+                stream.buffer.drain(..=position);
+                stream
+            }
+            None => {
+                self.stats.demand_miss_count += 1;
+                let position = self
+                    .streams
+                    .iter()
+                    .position(|stream| match stream.direction {
+                        Some(direction) => line == stream.last_line + direction,
+                        None => (line - stream.last_line).abs() == 1,
+                    });
+                let Some(position) = position else {
+                    if self.streams.len() >= STREAM_NUM {
+                        self.streams.pop_front();
+                    }
+                    self.streams.push_back(Stream {
+                        last_line: line,
+                        direction: None,
+                        buffer: VecDeque::with_capacity(self.degree),
+                    });
+                    return;
+                };
+                let mut stream = self.streams.remove(position).unwrap();
+                stream.direction = Some(line - stream.last_line);
+                stream.buffer.clear();
+                stream
+            }
+        };
+        stream.last_line = line;
+        self.fill_stream_buffer(&mut stream);
+        self.streams.push_back(stream);
+    }
+
+    /// Prefetches lines into the buffer of `stream` until it holds `degree` lines.
+    fn fill_stream_buffer(&mut self, stream: &mut Stream) {
+        let direction = stream.direction.unwrap();
+        let mut next_line = stream.buffer.back().unwrap_or(&stream.last_line) + direction;
+        while stream.buffer.len() < self.degree && self.line_to_addr(next_line).is_some() {
+            stream.buffer.push_back(next_line);
+            self.stats.issued_count += 1;
+            next_line += direction;
+        }
+    }
+
+    /// Records that `line_addr` was filled by a prefetch, evicting `victim_line_addr`.
+    pub fn on_prefetch_fill(&mut self, line_addr: Address, victim_line_addr: Option<Address>) {
+        self.stats.issued_count += 1;
+        self.prefetched_lines.insert(line_addr);
+        self.lines_evicted_by_prefetch.remove(&line_addr);
+        if let Some(victim_line_addr) = victim_line_addr {
+            self.prefetched_lines.remove(&victim_line_addr);
+            self.lines_evicted_by_prefetch.insert(victim_line_addr);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stride_confidence() {
+        let mut prefetcher = Prefetcher::new(PrefetcherKind::Stride, 2, 16, 1 << 20);
+        assert_eq!(prefetcher.on_demand_access(0x40, 0x100, false), vec![]);
+        assert_eq!(prefetcher.on_demand_access(0x40, 0x140, false), vec![]);
+        assert_eq!(prefetcher.on_demand_access(0x40, 0x180, true), vec![]);
+        assert_eq!(
+            prefetcher.on_demand_access(0x40, 0x1c0, true),
+            vec![0x200, 0x240]
+        );
+        // one irregular access lowers the confidence below the threshold
+        assert_eq!(prefetcher.on_demand_access(0x40, 0x1c4, true), vec![]);
+        assert_eq!(
+            prefetcher.on_demand_access(0x40, 0x204, true),
+            vec![0x240, 0x280]
+        );
+        // another load on the same table entry starts over
+        let other_pc = 0x40 + 4 * STRIDE_TABLE_SIZE as Address;
+        assert_eq!(prefetcher.on_demand_access(other_pc, 0x300, false), vec![]);
+        assert_eq!(prefetcher.on_demand_access(0x40, 0x244, false), vec![]);
+    }
+
+    #[test]
+    fn test_stream_buffers() {
+        let mut prefetcher = Prefetcher::new(PrefetcherKind::Stream, 2, 16, 1 << 20);
+        let buffered_lines = |prefetcher: &Prefetcher| {
+            let mut lines: Vec<i64> = prefetcher
+                .streams
+                .iter()
+                .flat_map(|stream| stream.buffer.iter().copied())
+                .collect();
+            lines.sort();
+            lines
+        };
+        // stream buffers never fill the cache
+        assert_eq!(prefetcher.on_demand_access(0, 0xa0, false), vec![]);
+        assert_eq!(prefetcher.on_demand_access(0, 0x1000, false), vec![]);
+        assert_eq!(prefetcher.on_demand_access(0, 0x94, false), vec![]);
+        assert_eq!(buffered_lines(&prefetcher), vec![0x7, 0x8]);
+        assert_eq!(prefetcher.on_demand_access(0, 0x1010, false), vec![]);
+        assert_eq!(buffered_lines(&prefetcher), vec![0x7, 0x8, 0x102, 0x103]);
+        // a miss to a buffered line promotes it and tops up its buffer
+        prefetcher.on_demand_access(0, 0x88, false);
+        assert_eq!(buffered_lines(&prefetcher), vec![0x6, 0x7, 0x102, 0x103]);
+        // skipping a line drops it from the buffer
+        prefetcher.on_demand_access(0, 0x1030, false);
+        assert_eq!(buffered_lines(&prefetcher), vec![0x6, 0x7, 0x104, 0x105]);
+        // hits are served by the cache and do not touch the buffers
+        prefetcher.on_demand_access(0, 0x70, true);
+        assert_eq!(buffered_lines(&prefetcher), vec![0x6, 0x7, 0x104, 0x105]);
+        // a descending stream does not continue upwards
+        prefetcher.on_demand_access(0, 0x90, false);
+        assert_eq!(buffered_lines(&prefetcher), vec![0x6, 0x7, 0x104, 0x105]);
+        let stats = prefetcher.get_stats();
+        assert_eq!(stats.issued_count, 7);
+        assert_eq!(stats.useful_count, 2);
+        assert_eq!(stats.demand_miss_count, 5);
+        assert_eq!(stats.polluting_miss_count, 0);
+        // lines below address 0 are not prefetched
+        let mut prefetcher = Prefetcher::new(PrefetcherKind::Stream, 2, 16, 1 << 20);
+        prefetcher.on_demand_access(0, 0x20, false);
+        prefetcher.on_demand_access(0, 0x10, false);
+        assert_eq!(buffered_lines(&prefetcher), vec![0x0]);
+    }
+
+    #[test]
+    fn test_useful_and_polluting_prefetches() {
+        let mut prefetcher = Prefetcher::new(PrefetcherKind::NextLine, 1, 16, 1 << 20);
+        assert_eq!(prefetcher.on_demand_access(0, 0x100, false), vec![0x110]);
+        prefetcher.on_prefetch_fill(0x110, Some(0x200));
+        assert_eq!(prefetcher.on_demand_access(0, 0x114, true), vec![0x120]);
+        assert_eq!(prefetcher.on_demand_access(0, 0x118, true), vec![]);
+        assert_eq!(prefetcher.on_demand_access(0, 0x200, false), vec![0x210]);
+        assert_eq!(prefetcher.on_demand_access(0, 0x200, false), vec![0x210]);
+        // a prefetched line evicted before its first use is not useful
+        prefetcher.on_prefetch_fill(0x120, None);
+        prefetcher.on_prefetch_fill(0x300, Some(0x120));
+        assert_eq!(prefetcher.on_demand_access(0, 0x120, false), vec![0x130]);
+        let stats = prefetcher.get_stats();
+        assert_eq!(stats.issued_count, 3);
+        assert_eq!(stats.useful_count, 1);
+        assert_eq!(stats.demand_miss_count, 4);
+        assert_eq!(stats.polluting_miss_count, 2);
+    }
+}
@@ -6,7 +6,7 @@ pub fn load_sld_file(file_path: &str) -> Vec<String> {
 
     if let Ok(file) = File::open(file_path) {
         let reader = io::BufReader::new(file);
-        for line in reader.lines().flatten() {
+        for line in reader.lines().map_while(Result::ok) {
             let iter = line.split_whitespace();
             for token in iter {
                 sld_vec.push(token.to_string());
@@ -0,0 +1,190 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use crate::types::*;
+
+pub fn parse_power_of_two(s: &str) -> Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(value) if value.is_power_of_two() => Ok(value),
+        _ => Err(format!("'{}' is not a power of two", s)),
+    }
+}
+
+/// LRU stacks of one (line size, set number) geometry.
+/// `histogram[d]` counts accesses whose reuse distance within their set is `d`,
+/// and the last bucket counts first touches and distances of `max_way_num` or more.
+struct StackDistanceConfig {
+    line_size: usize,
+    set_num: usize,
+    offset_bit_num: usize,
+    stacks: Vec<VecDeque<Address>>,
+    histogram: Vec<u64>,
+}
+
+impl StackDistanceConfig {
+    fn new(line_size: usize, set_num: usize, max_way_num: usize) -> Self {
+        StackDistanceConfig {
+            line_size,
+            set_num,
+            offset_bit_num: line_size.trailing_zeros() as usize,
+            stacks: vec![VecDeque::with_capacity(max_way_num + 1); set_num],
+            histogram: vec![0; max_way_num + 1],
+        }
+    }
+
+    fn record(&mut self, addr: Address, max_way_num: usize) {
+        let line = addr >> self.offset_bit_num;
+        let stack = &mut self.stacks[line as usize & (self.set_num - 1)];
+        match stack.iter().position(|&l| l == line) {
+            Some(distance) => {
+                self.histogram[distance] += 1;
+                stack.remove(distance);
+            }
+            None => {
+                self.histogram[max_way_num] += 1;
+                if stack.len() >= max_way_num {
+                    stack.pop_back();
+                }
+            }
+        }
+        stack.push_front(line);
+    }
+
+    fn get_hit_rate(&self, way_num: usize) -> f64 {
+        let access_count: u64 = self.histogram.iter().sum();
+        if access_count == 0 {
+            return 0.0;
+        }
+        let hit_count: u64 = self.histogram[..way_num].iter().sum();
+        hit_count as f64 / access_count as f64
+    }
+}
+
+/// Computes the hit rates of every LRU cache with the given line sizes, set numbers
+/// (powers of two up to `max_set_num`) and associativities (up to `max_way_num`) in one run.
+pub struct StackDistanceAnalyzer {
+    max_way_num: usize,
+    configs: Vec<StackDistanceConfig>,
+}
+
+impl StackDistanceAnalyzer {
+    pub fn new(line_sizes: &[usize], max_set_num: usize, max_way_num: usize) -> Self {
+        let mut configs = vec![];
+        for &line_size in line_sizes {
+            let mut set_num = 1;
+            while set_num <= max_set_num {
+                configs.push(StackDistanceConfig::new(line_size, set_num, max_way_num));
+                set_num <<= 1;
+            }
+        }
+        StackDistanceAnalyzer {
+            max_way_num,
+            configs,
+        }
+    }
+
+    pub fn record(&mut self, addr: Address) {
+        for config in &mut self.configs {
+            config.record(addr, self.max_way_num);
+        }
+    }
+
+    fn get_way_nums(&self) -> Vec<usize> {
+        let mut way_nums = vec![];
+        let mut way_num = 1;
+        while way_num <= self.max_way_num {
+            way_nums.push(way_num);
+            way_num <<= 1;
+        }
+        way_nums
+    }
+
+    pub fn show(&self) {
+        println!("---------- stack distance ----------");
+        let way_nums = self.get_way_nums();
+        let mut line_size = 0;
+        for config in &self.configs {
+            if config.line_size != line_size {
+                line_size = config.line_size;
+                println!(
+                    "line size: {} (hit rate % by set num and way num)",
+                    line_size
+                );
+                print!("sets\\ways ");
+                for way_num in &way_nums {
+                    print!("{:>10}", way_num);
+                }
+                println!();
+            }
+            print!("{:<10}", config.set_num);
+            for &way_num in &way_nums {
+                print!("{:>10.3}", config.get_hit_rate(way_num) * 100.0);
+            }
+            println!();
+        }
+    }
+
+    /// Writes the raw reuse distance histograms followed by the hit rate of every
+    /// configuration, whose capacity is `line_size * set_num * way_num` bytes.
+    pub fn output_file(&self, path: &str) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "# line_size set_num distance count")?;
+        for config in &self.configs {
+            for (distance, count) in config.histogram.iter().enumerate() {
+                let distance = if distance == self.max_way_num {
+                    "inf".to_string()
+                } else {
+                    distance.to_string()
+                };
+                writeln!(
+                    writer,
+                    "{} {} {} {}",
+                    config.line_size, config.set_num, distance, count
+                )?;
+            }
+        }
+        writeln!(writer, "# line_size set_num way_num capacity hit_rate")?;
+        for config in &self.configs {
+            for way_num in self.get_way_nums() {
+                writeln!(
+                    writer,
+                    "{} {} {} {} {:.6}",
+                    config.line_size,
+                    config.set_num,
+                    way_num,
+                    config.line_size * config.set_num * way_num,
+                    config.get_hit_rate(way_num)
+                )?;
+            }
+        }
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reuse_distance() {
+        let mut config = StackDistanceConfig::new(4, 1, 4);
+        for addr in [0, 4, 8, 0, 8, 12, 16, 0] {
+            config.record(addr, 4);
+        }
+        // 0 is reused at distance 2, 8 at distance 1 and 0 again at distance 3
+        assert_eq!(config.histogram, vec![0, 1, 1, 1, 5]);
+        assert_eq!(config.get_hit_rate(2), 1.0 / 8.0);
+        assert_eq!(config.get_hit_rate(3), 2.0 / 8.0);
+    }
+
+    #[test]
+    fn test_sets_are_independent() {
+        let mut config = StackDistanceConfig::new(4, 2, 2);
+        for addr in [0, 4, 8, 0] {
+            config.record(addr, 2);
+        }
+        // 0 and 8 map to set 0 and 4 to set 1, so 0 is reused at distance 1
+        assert_eq!(config.histogram, vec![0, 1, 3]);
+    }
+}
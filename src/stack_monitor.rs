@@ -0,0 +1,191 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use crate::types::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum StackMonitorMode {
+    /// Report the collision of the stack and the heap and continue
+    Warn,
+    /// Report the collision of the stack and the heap and stop the program
+    Stop,
+}
+
+/// Tracks the stack pointer growing down and the heap pointer growing up.
+pub struct StackMonitor {
+    mode: StackMonitorMode,
+    heap_register: usize,
+    initial_sp: Address,
+    initial_hp: Address,
+    min_sp: (Address, Address),
+    max_hp: (Address, Address),
+    min_separation: (i64, InstructionCount),
+    collision_count: usize,
+    colliding: bool,
+    log_writer: Option<BufWriter<File>>,
+}
+
+impl StackMonitor {
+    pub fn new(
+        mode: StackMonitorMode,
+        heap_register: usize,
+        sp: Address,
+        hp: Address,
+        log_file_path: Option<&str>,
+    ) -> io::Result<Self> {
+        let log_writer = match log_file_path {
+            Some(path) => {
+                let mut writer = BufWriter::new(File::create(path)?);
+                writeln!(writer, "# inst_count pc sp hp separation")?;
+                Some(writer)
+            }
+            None => None,
+        };
+        Ok(StackMonitor {
+            mode,
+            heap_register,
+            initial_sp: sp,
+            initial_hp: hp,
+            min_sp: (sp, 0),
+            max_hp: (hp, 0),
+            min_separation: (sp as i64 - hp as i64, 0),
+            collision_count: 0,
+            colliding: false,
+            log_writer,
+        })
+    }
+
+    pub fn get_heap_register(&self) -> usize {
+        self.heap_register
+    }
+
+    /// Updates the extremes with the current stack and heap pointers written by the instruction
+    /// at `pc`. Returns true if the program should stop.
+    pub fn update(
+        &mut self,
+        sp: Address,
+        hp: Address,
+        pc: Address,
+        inst_count: InstructionCount,
+    ) -> bool {
+        let mut new_extreme = false;
+        if sp < self.min_sp.0 {
+            self.min_sp = (sp, pc);
+            new_extreme = true;
+        }
+        if hp > self.max_hp.0 {
+            self.max_hp = (hp, pc);
+            new_extreme = true;
+        }
+        let separation = sp as i64 - hp as i64;
+        if separation < self.min_separation.0 {
+            self.min_separation = (separation, inst_count);
+        }
+        if new_extreme {
+            if let Some(writer) = &mut self.log_writer {
+                let result = writeln!(
+                    writer,
+                    "{} {} {:#010x} {:#010x} {}",
+                    inst_count, pc, sp, hp, separation
+                );
+                if let Err(e) = result {
+                    eprintln!(
+                        "\nFailed in writing stack monitor log ({}). Logging is stopped.",
+                        e
+                    );
+                    self.log_writer = None;
+                }
+            }
+        }
+
+        let colliding = separation < 0;
+        let newly_colliding = colliding && !self.colliding;
+        self.colliding = colliding;
+        if !newly_colliding {
+            return false;
+        }
+        self.collision_count += 1;
+        eprintln!(
+            "\n{} stack/heap collision: sp {:#010x} < hp (x{}) {:#010x}",
+            match self.mode {
+                StackMonitorMode::Warn => "warning:",
+                StackMonitorMode::Stop => "error:",
+            },
+            sp,
+            self.heap_register,
+            hp
+        );
+        eprintln!("    pc: {}, inst_count: {}", pc, inst_count);
+        self.mode == StackMonitorMode::Stop
+    }
+
+    pub fn show(&mut self) {
+        if let Some(writer) = &mut self.log_writer {
+            if let Err(e) = writer.flush() {
+                eprintln!("Failed in writing stack monitor log ({}).", e);
+            }
+        }
+        println!("---------- stack monitor ----------");
+        println!("min sp: {:#010x} (pc: {})", self.min_sp.0, self.min_sp.1);
+        println!(
+            "max hp (x{}): {:#010x} (pc: {})",
+            self.heap_register, self.max_hp.0, self.max_hp.1
+        );
+        println!(
+            "peak stack usage: {} bytes",
+            self.initial_sp.saturating_sub(self.min_sp.0)
+        );
+        println!(
+            "peak heap usage: {} bytes",
+            self.max_hp.0.saturating_sub(self.initial_hp)
+        );
+        println!(
+            "min separation: {} bytes (inst_count: {})",
+            self.min_separation.0, self.min_separation.1
+        );
+        println!("collision count: {}", self.collision_count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collision() {
+        let mut stack_monitor =
+            StackMonitor::new(StackMonitorMode::Warn, 4, 0x1000, 0x800, None).unwrap();
+        assert!(!stack_monitor.update(0xf00, 0x900, 4, 1));
+        assert_eq!(stack_monitor.min_sp, (0xf00, 4));
+        assert_eq!(stack_monitor.max_hp, (0x900, 4));
+        assert!(!stack_monitor.update(0x9f0, 0xa00, 8, 2));
+        assert_eq!(stack_monitor.collision_count, 1);
+        // a collision is reported once until the pointers separate again
+        assert!(!stack_monitor.update(0x9e0, 0xa00, 12, 3));
+        assert!(!stack_monitor.update(0xf00, 0xa00, 16, 4));
+        assert!(!stack_monitor.update(0x9f0, 0xa00, 20, 5));
+        assert_eq!(stack_monitor.collision_count, 2);
+        assert_eq!(stack_monitor.min_sp, (0x9e0, 12));
+        assert_eq!(stack_monitor.min_separation, (-0x20, 3));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_write_error_stops_logging() {
+        let mut stack_monitor =
+            StackMonitor::new(StackMonitorMode::Warn, 4, 0x1000, 0x800, Some("/dev/full")).unwrap();
+        for i in 0..1000 {
+            stack_monitor.update(0x1000 - i * 4, 0x800, 4, i as InstructionCount);
+        }
+        assert!(stack_monitor.log_writer.is_none());
+        assert_eq!(stack_monitor.min_sp, (0x1000 - 999 * 4, 4));
+    }
+
+    #[test]
+    fn test_stop_on_collision() {
+        let mut stack_monitor =
+            StackMonitor::new(StackMonitorMode::Stop, 4, 0x1000, 0x800, None).unwrap();
+        assert!(!stack_monitor.update(0x1000, 0x1000, 4, 1));
+        assert!(stack_monitor.update(0x1000, 0x1004, 8, 2));
+    }
+}
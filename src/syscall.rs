@@ -0,0 +1,262 @@
+use std::io::{self, Read, Write};
+
+use crate::core::*;
+use crate::types::*;
+
+// system call numbers of the Linux ABI, which libgloss follows on RISC-V
+const SYS_READ: Int = 63;
+const SYS_WRITE: Int = 64;
+const SYS_EXIT: Int = 93;
+const SYS_CLOCK_GETTIME: Int = 113;
+const SYS_GETTIMEOFDAY: Int = 169;
+const SYS_BRK: Int = 214;
+const SYS_CLOCK_GETTIME64: Int = 403;
+
+const EIO: Int = 5;
+const EBADF: Int = 9;
+const EINVAL: Int = 22;
+const ENOSYS: Int = 38;
+
+const STDIN: Int = 0;
+const STDOUT: Int = 1;
+const STDERR: Int = 2;
+
+/// Buffers are copied in chunks of this size, so a large count does not allocate its
+/// whole size on the host.
+const CHUNK_SIZE: usize = 4096;
+
+/// Emulates the newlib system call `number` with the arguments in a0 to a2 and returns
+/// the value for a0, which is a negated errno on failure.
+/// stdout goes to the program output like `outchar`, stderr to the host stderr, and stdin
+/// is read from the host stdin. Buffers are accessed through the data cache like guest
+/// loads and stores of the `ecall`. Time is the cycle count at the clock frequency, so it is
+/// deterministic.
+pub fn system_call(core: &mut Core, number: Int, args: [Int; 3]) -> Int {
+    let [a0, a1, a2] = args;
+    match number {
+        SYS_READ => read(core, a0, a1 as Address, a2, &mut io::stdin()),
+        SYS_WRITE => write(core, a0, a1 as Address, a2),
+        SYS_EXIT => {
+            core.exit(a0);
+            0
+        }
+        SYS_CLOCK_GETTIME | SYS_CLOCK_GETTIME64 => {
+            store_time(core, a1 as Address, 1_000_000_000);
+            0
+        }
+        SYS_GETTIMEOFDAY => {
+            store_time(core, a0 as Address, 1_000_000);
+            0
+        }
+        SYS_BRK => brk(core, a0 as Address),
+        _ => {
+            eprintln!("Warning: unsupported system call {}.", number);
+            -ENOSYS
+        }
+    }
+}
+
+/// Returns the number of arguments in a0 to a2 that the system call `number` reads.
+pub fn get_argument_count(number: Int) -> usize {
+    match number {
+        SYS_READ | SYS_WRITE => 3,
+        SYS_CLOCK_GETTIME | SYS_CLOCK_GETTIME64 => 2,
+        SYS_EXIT | SYS_GETTIMEOFDAY | SYS_BRK => 1,
+        _ => 0,
+    }
+}
+
+/// Reads up to `count` bytes from `input`, stopping early at a short read like a read
+/// from a terminal or pipe does.
+fn read(core: &mut Core, fd: Int, buf: Address, count: Int, input: &mut impl Read) -> Int {
+    if fd != STDIN {
+        return -EBADF;
+    }
+    if count < 0 {
+        return -EINVAL;
+    }
+    let mut bytes = [0; CHUNK_SIZE];
+    let mut read_count = 0;
+    while read_count < count as usize {
+        let chunk_size = (count as usize - read_count).min(CHUNK_SIZE);
+        let chunk_count = match input.read(&mut bytes[..chunk_size]) {
+            Ok(chunk_count) => chunk_count,
+            Err(_) if read_count == 0 => return -EIO,
+            Err(_) => break,
+        };
+        for (i, &byte) in bytes[..chunk_count].iter().enumerate() {
+            core.store_byte(buf.wrapping_add((read_count + i) as Address), byte as Byte);
+        }
+        read_count += chunk_count;
+        if chunk_count < chunk_size {
+            break;
+        }
+    }
+    read_count as Int
+}
+
+fn write(core: &mut Core, fd: Int, buf: Address, count: Int) -> Int {
+    if fd != STDOUT && fd != STDERR {
+        return -EBADF;
+    }
+    if count < 0 {
+        return -EINVAL;
+    }
+    let mut bytes = Vec::with_capacity(CHUNK_SIZE);
+    for start in (0..count as Address).step_by(CHUNK_SIZE) {
+        let end = (start + CHUNK_SIZE as Address).min(count as Address);
+        bytes.clear();
+        bytes.extend((start..end).map(|i| core.load_ubyte(buf.wrapping_add(i))));
+        if fd == STDOUT {
+            for &byte in &bytes {
+                core.print_char(byte as Word);
+            }
+        } else if io::stderr().write_all(&bytes).is_err() {
+            return -EIO;
+        }
+    }
+    count
+}
+
+/// Stores the time as a 64-bit count of seconds followed by a 32-bit count of
+/// `1 / units_per_second`, the layout of `timespec` and `timeval` with 64-bit `time_t`.
+fn store_time(core: &mut Core, addr: Address, units_per_second: Cycle) {
+    if addr == 0 {
+        return;
+    }
+    let frequency = core.get_clock_frequency() as Cycle;
+    let cycle_count = core.get_cycle_count();
+    let seconds = cycle_count / frequency;
+    let units = cycle_count % frequency * units_per_second / frequency;
+    core.store_word(addr, seconds as Word);
+    core.store_word(addr.wrapping_add(4), (seconds >> 32) as Word);
+    core.store_word(addr.wrapping_add(8), units as Word);
+}
+
+/// Moves the program break to `addr` unless it is below the end of the loaded program,
+/// and returns the new break. `brk(0)` returns the current break.
+fn brk(core: &mut Core, addr: Address) -> Int {
+    if addr >= core.get_heap_start() {
+        core.set_program_break(addr);
+    }
+    core.get_program_break() as Int
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::tests::run_program;
+    use crate::isa::Isa;
+    use crate::memory_check::MemoryCheck;
+    use crate::memory_trace::*;
+
+    #[test]
+    fn test_system_call() {
+        let mut core = Core::new();
+        assert_eq!(system_call(&mut core, SYS_WRITE, [STDIN, 0x100, 3]), -EBADF);
+        assert_eq!(system_call(&mut core, SYS_READ, [STDOUT, 0x100, 3]), -EBADF);
+        assert_eq!(system_call(&mut core, 1, [0, 0, 0]), -ENOSYS);
+
+        core.load_data(0x1000, &[0; 4]).unwrap();
+        assert_eq!(system_call(&mut core, SYS_BRK, [0, 0, 0]), 0x1010);
+        assert_eq!(system_call(&mut core, SYS_BRK, [0x2000, 0, 0]), 0x2000);
+        assert_eq!(system_call(&mut core, SYS_BRK, [0x800, 0, 0]), 0x2000);
+
+        system_call(&mut core, SYS_EXIT, [3, 0, 0]);
+        assert_eq!(core.get_exit_code(), Some(3));
+    }
+
+    #[test]
+    fn test_exit_reads_only_a0() {
+        let mut core = Core::new();
+        core.set_isa(Isa::Rv32imf);
+        core.enable_uninit_check();
+        run_program(
+            &mut core,
+            &[
+                0x05d00893, // li a7, 93
+                0x00000513, // li a0, 0
+                0x00000073, // ecall
+            ],
+        );
+        assert_eq!(core.get_exit_code(), Some(0));
+        let uninit_checker = core.get_uninit_checker().unwrap();
+        assert_eq!(uninit_checker.get_register_read_count(), 0);
+    }
+
+    #[test]
+    fn test_write() {
+        let mut core = Core::new();
+        core.load_data(0x100, &[u32::from_le_bytes(*b"abcd")])
+            .unwrap();
+        assert_eq!(system_call(&mut core, SYS_WRITE, [STDOUT, 0x101, 3]), 3);
+        assert_eq!(core.get_output(), b"bcd");
+        assert_eq!(
+            system_call(&mut core, SYS_WRITE, [STDOUT, 0x100, -1]),
+            -EINVAL
+        );
+        assert_eq!(core.get_output(), b"bcd");
+    }
+
+    #[test]
+    fn test_write_with_bubble_in_memory_stage() {
+        let path = std::env::temp_dir().join(format!(
+            "test_write_with_bubble_in_memory_stage-{}",
+            std::process::id()
+        ));
+        let path = path.to_str().unwrap();
+        let mut core = Core::new();
+        core.set_isa(Isa::Rv32imf);
+        core.enable_uninit_check();
+        core.set_memory_check(MemoryCheck::Trap);
+        core.set_mshr_timing_model(2, 10).unwrap();
+        core.set_memory_tracer(MemoryTracer::new(path, TraceFormat::Dinero, None).unwrap());
+        core.load_data(0x100, &[u32::from_le_bytes(*b"abcd")])
+            .unwrap();
+        run_program(
+            &mut core,
+            &[
+                0x00100513, // li a0, 1
+                0x10000593, // li a1, 0x100
+                0x00400613, // li a2, 4
+                0x04000893, // li a7, 64
+                0x0040006f, // j 4
+                0x00000073, // ecall
+                0x05d00893, // li a7, 93
+                0x00000513, // li a0, 0
+                0x00000073, // ecall
+            ],
+        );
+        assert_eq!(core.get_output(), b"abcd");
+        assert_eq!(core.get_exit_code(), Some(0));
+        // the buffer is read by the first ecall at pc 20
+        let trace = std::fs::read_to_string(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        let lines: Vec<&str> = trace.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines.iter().all(
+            |line| line.starts_with("0 0000010") && line.split(' ').nth(2) == Some("00000014")
+        ));
+    }
+
+    #[test]
+    fn test_read() {
+        let mut core = Core::new();
+        let text = b"hello";
+        assert_eq!(read(&mut core, STDIN, 0x101, 3, &mut &text[..]), 3);
+        assert_eq!(core.load_word(0x100).to_le_bytes(), *b"\0hel");
+        // a short read stops at the end of the input
+        assert_eq!(read(&mut core, STDIN, 0x200, 100, &mut &text[..]), 5);
+        assert_eq!(core.load_ubyte(0x204), b'o');
+        assert_eq!(core.load_ubyte(0x205), 0);
+        // a read longer than a chunk is split into several
+        let long_text = vec![b'x'; CHUNK_SIZE + 10];
+        let count = long_text.len() as Int;
+        assert_eq!(
+            read(&mut core, STDIN, 0x1000, count, &mut &long_text[..]),
+            count
+        );
+        assert_eq!(core.load_ubyte(0x1000 + count as Address - 1), b'x');
+        assert_eq!(read(&mut core, STDIN, 0x1000, -1, &mut &text[..]), -EINVAL);
+    }
+}
@@ -1,11 +1,11 @@
-// pub type Byte = i8;
-// pub type UByte = u8;
-// pub type Half = i16;
-// pub type UHalf = u16;
+pub type Byte = i8;
+pub type UByte = u8;
+pub type Half = i16;
+pub type UHalf = u16;
 pub type Word = i32;
 // pub type Double = f64;
 pub type Int = i32;
-pub type Float = f32;
+// pub type Float = f32;
 pub type MemoryValue = u32;
 pub type Address = u32;
 pub type InstructionValue = u32;
@@ -32,3 +32,4 @@ pub type Fs1 = u8;
 pub type Funct2 = u8;
 pub type Fd = u8;
 pub type InstructionCount = u128;
+pub type Cycle = u128;
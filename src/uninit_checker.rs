@@ -0,0 +1,174 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::types::*;
+use crate::utils::*;
+
+const PAGE_WORD_NUM: usize = 1024;
+const PAGE_BITMAP_SIZE: usize = PAGE_WORD_NUM / 64;
+
+/// Shadow "initialized" state of memory words and registers.
+/// The memory shadow is allocated by 4 KiB pages on the first write to each page.
+pub struct UninitChecker {
+    initialized_pages: HashMap<usize, Box<[u64; PAGE_BITMAP_SIZE]>>,
+    int_registers: [bool; 32],
+    float_registers: [bool; 32],
+    reported: HashSet<(Address, bool)>,
+    memory_read_count: usize,
+    register_read_count: usize,
+}
+
+impl UninitChecker {
+    pub fn new() -> Self {
+        let mut int_registers = [false; 32];
+        for index in [ZERO, RA, SP] {
+            int_registers[index] = true;
+        }
+        UninitChecker {
+            initialized_pages: HashMap::new(),
+            int_registers,
+            float_registers: [false; 32],
+            reported: HashSet::new(),
+            memory_read_count: 0,
+            register_read_count: 0,
+        }
+    }
+
+    fn get_position(addr: Address) -> (usize, usize, u64) {
+        let word_index = addr as usize >> 2;
+        let page = word_index / PAGE_WORD_NUM;
+        let offset = word_index % PAGE_WORD_NUM;
+        (page, offset / 64, 1 << (offset % 64))
+    }
+
+    pub fn mark_word(&mut self, addr: Address) {
+        let (page, index, bit) = Self::get_position(addr);
+        let bitmap = self
+            .initialized_pages
+            .entry(page)
+            .or_insert_with(|| Box::new([0; PAGE_BITMAP_SIZE]));
+        bitmap[index] |= bit;
+    }
+
+    pub fn is_word_initialized(&self, addr: Address) -> bool {
+        let (page, index, bit) = Self::get_position(addr);
+        match self.initialized_pages.get(&page) {
+            Some(bitmap) => bitmap[index] & bit != 0,
+            None => false,
+        }
+    }
+
+    pub fn mark_register(&mut self, register: &RegisterId) {
+        match *register {
+            RegisterId::Int(index) => self.int_registers[index as usize] = true,
+            RegisterId::Float(index) => self.float_registers[index as usize] = true,
+        }
+    }
+
+    pub fn is_register_initialized(&self, register: &RegisterId) -> bool {
+        match *register {
+            RegisterId::Int(index) => self.int_registers[index as usize],
+            RegisterId::Float(index) => self.float_registers[index as usize],
+        }
+    }
+
+    /// Reports a load from a never-written word, once per instruction.
+    pub fn check_load(&mut self, addr: Address, pc: Address, inst_string: impl FnOnce() -> String) {
+        if self.is_word_initialized(addr) {
+            return;
+        }
+        self.memory_read_count += 1;
+        if self.reported.insert((pc, true)) {
+            eprintln!(
+                "\nwarning: load from uninitialized memory at {:#010x}\n    pc: {} ({})",
+                addr,
+                pc,
+                inst_string()
+            );
+        }
+    }
+
+    /// Reports a read of a never-written register, once per instruction.
+    pub fn check_register(
+        &mut self,
+        register: &RegisterId,
+        pc: Address,
+        inst_string: impl FnOnce() -> String,
+    ) {
+        if self.is_register_initialized(register) {
+            return;
+        }
+        self.register_read_count += 1;
+        if self.reported.insert((pc, false)) {
+            let name = match register {
+                RegisterId::Int(index) => format!("x{}", index),
+                RegisterId::Float(index) => format!("f{}", index),
+            };
+            eprintln!(
+                "\nwarning: read of uninitialized register {}\n    pc: {} ({})",
+                name,
+                pc,
+                inst_string()
+            );
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn get_register_read_count(&self) -> usize {
+        self.register_read_count
+    }
+
+    pub fn show(&self) {
+        println!("---------- uninitialized reads ----------");
+        println!(
+            "uninitialized memory read count: {}",
+            self.memory_read_count
+        );
+        println!(
+            "uninitialized register read count: {}",
+            self.register_read_count
+        );
+        println!(
+            "instructions reported: {}",
+            self.reported
+                .iter()
+                .map(|(pc, _)| pc)
+                .collect::<HashSet<_>>()
+                .len()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::*;
+
+    #[test]
+    fn test_memory_shadow() {
+        let mut checker = UninitChecker::new();
+        assert!(!checker.is_word_initialized(0x1000));
+        checker.mark_word(0x1002);
+        assert!(checker.is_word_initialized(0x1000));
+        assert!(!checker.is_word_initialized(0x1004));
+        assert!(!checker.is_word_initialized(0x1000 + 4 * PAGE_WORD_NUM as Address));
+        checker.check_load(0x2000, 8, || "lw x5, 0(x6)".to_string());
+        checker.check_load(0x2004, 8, || "lw x5, 0(x6)".to_string());
+        assert_eq!(checker.memory_read_count, 2);
+        assert_eq!(checker.reported.len(), 1);
+    }
+
+    #[test]
+    fn test_register_shadow() {
+        let checker = UninitChecker::new();
+        assert!(checker.is_register_initialized(&RegisterId::Int(SP as u8)));
+        assert!(!checker.is_register_initialized(&RegisterId::Int(5)));
+        assert!(!checker.is_register_initialized(&RegisterId::Float(0)));
+    }
+
+    #[test]
+    fn test_load_without_instruction_in_memory_stage() {
+        let mut core = Core::new();
+        core.enable_uninit_check();
+        assert_eq!(core.load_word(0x1000), 0);
+    }
+}
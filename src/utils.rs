@@ -1,32 +1,37 @@
+use crate::types::*;
+
 pub const ZERO: usize = 0;
 pub const RA: usize = 1;
 pub const SP: usize = 2;
+pub const A0: usize = 10;
+pub const A1: usize = 11;
+pub const A2: usize = 12;
+pub const A7: usize = 17;
 
-// pub fn u8_to_i8(value: u8) -> i8 {
-//     if value <= i8::MAX as u8 {
-//         value as i8
-//     } else {
-//         (value as i16 - (u8::MAX as i16 + 1)) as i8
-//     }
-// }
+pub fn u8_to_i8(value: u8) -> i8 {
+    if value <= i8::MAX as u8 {
+        value as i8
+    } else {
+        (value as i16 - (u8::MAX as i16 + 1)) as i8
+    }
+}
 
-// pub fn i8_to_u8(value: i8) -> u8 {
-//     if value >= 0 {
-//         value as u8
-//     } else {
-//         (value as i16 + (u8::MAX as i16 + 1)) as u8
-//     }
-// }
+pub fn i8_to_u8(value: i8) -> u8 {
+    if value >= 0 {
+        value as u8
+    } else {
+        (value as i16 + (u8::MAX as i16 + 1)) as u8
+    }
+}
 
-// pub fn u16_to_i16(value: u16) -> i16 {
-//     if value <= i16::MAX as u16 {
-//         value as i16
-//     } else {
-//         (value as i32 - (u16::MAX as i32 + 1)) as i16
-//     }
-// }
+pub fn u16_to_i16(value: u16) -> i16 {
+    if value <= i16::MAX as u16 {
+        value as i16
+    } else {
+        (value as i32 - (u16::MAX as i32 + 1)) as i16
+    }
+}
 
-#[allow(dead_code)]
 pub fn i16_to_u16(value: i16) -> u16 {
     if value >= 0 {
         value as u16
@@ -51,6 +56,38 @@ pub fn i32_to_u32(value: i32) -> u32 {
     }
 }
 
+pub fn parse_address(s: &str) -> Result<Address, String> {
+    let result = if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Address::from_str_radix(hex, 16)
+    } else {
+        s.parse::<Address>()
+    };
+    result.map_err(|e| format!("invalid address '{}' ({})", s, e))
+}
+
+/// Parses `START:END` (END exclusive) or `START+LENGTH` into a half-open address range.
+pub fn parse_address_range(s: &str) -> Result<(Address, Address), String> {
+    let (start, end) = if let Some((start, end)) = s.split_once(':') {
+        (parse_address(start)?, parse_address(end)?)
+    } else if let Some((start, length)) = s.split_once('+') {
+        let start = parse_address(start)?;
+        let length = parse_address(length)?;
+        let end = start
+            .checked_add(length)
+            .ok_or_else(|| format!("address range '{}' overflows", s))?;
+        (start, end)
+    } else {
+        return Err(format!(
+            "invalid address range '{}' (expected START:END or START+LENGTH)",
+            s
+        ));
+    };
+    if start >= end {
+        return Err(format!("address range '{}' is empty", s));
+    }
+    Ok((start, end))
+}
+
 #[allow(dead_code)]
 pub const RED: &str = "31";
 #[allow(dead_code)]
@@ -72,3 +109,22 @@ pub fn print_filled_with_space(s: &String, n: usize) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_address_range() {
+        assert_eq!(parse_address_range("0x1000:0x2000"), Ok((0x1000, 0x2000)));
+        assert_eq!(parse_address_range("4096+0x10"), Ok((4096, 4112)));
+        assert_eq!(parse_address_range("0XfF:256"), Ok((255, 256)));
+        assert!(parse_address_range("0x1000").is_err());
+        assert!(parse_address_range("0x1000:0xg000").is_err());
+        assert!(parse_address_range("-1:4").is_err());
+        assert!(parse_address_range("0x2000:0x1000").is_err());
+        assert!(parse_address_range("0x10+0").is_err());
+        assert!(parse_address_range("0xffffffff+2").is_err());
+        assert!(parse_address_range("0:0x100000000").is_err());
+    }
+}